@@ -15,8 +15,21 @@ criterion_group!(
         decode::bench_decode_small,
         decode::bench_decode_medium,
         decode::bench_decode_large,
+        decode::bench_decode_printable_ascii,
         encode::bench_encode_small,
         encode::bench_encode_medium,
         encode::bench_encode_large
 );
+#[cfg(feature = "rayon")]
+criterion_group!(
+    name = parallel_benches;
+    config = Criterion::default();
+    targets =
+        compare::bench_compare_parallel,
+        encode::bench_encode_parallel
+);
+
+#[cfg(not(feature = "rayon"))]
 criterion_main!(benches);
+#[cfg(feature = "rayon")]
+criterion_main!(benches, parallel_benches);
@@ -46,5 +46,41 @@ pub fn bench_compare_large(c: &mut Criterion) {
     });
 }
 
+#[cfg(feature = "rayon")]
+pub fn bench_compare_parallel(c: &mut Criterion) {
+    let secret_message = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG".repeat(200);
+    let cover_text = "This is a much much longer text to compare. It is a full sentence that is complex enough for a good benchmark test. This ould ideally take the longest time to compare among the three.".repeat(3000);
+    let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ ";
+    let mut group = c.benchmark_group("compare_huge");
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            compare(
+                black_box(&secret_message),
+                black_box(&cover_text),
+                black_box(character_set),
+            )
+        });
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            stego_wps::compare_parallel(
+                black_box(&secret_message),
+                black_box(&cover_text),
+                black_box(character_set),
+            )
+        });
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "rayon"))]
 criterion_group!(benches, bench_compare_small, bench_compare_medium, bench_compare_large);
+#[cfg(feature = "rayon")]
+criterion_group!(
+    benches,
+    bench_compare_small,
+    bench_compare_medium,
+    bench_compare_large,
+    bench_compare_parallel
+);
 criterion_main!(benches);
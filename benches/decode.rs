@@ -25,6 +25,20 @@ pub fn bench_decode_large(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_decode_small, bench_decode_medium, bench_decode_large);
+pub fn bench_decode_printable_ascii(c: &mut Criterion) {
+    let character_set: String = (' '..='~').collect();
+    let encoded = (1..=10_000).map(|i| i % 95 + 1).collect::<Vec<usize>>();
+    c.bench_function("decode_printable_ascii", |b| {
+        b.iter(|| decode(black_box(&encoded), black_box(&character_set)));
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_decode_small,
+    bench_decode_medium,
+    bench_decode_large,
+    bench_decode_printable_ascii
+);
 criterion_main!(benches);
 
@@ -22,6 +22,28 @@ pub fn bench_encode_large(c: &mut Criterion) {
     });
 }
 
+#[cfg(feature = "rayon")]
+pub fn bench_encode_parallel(c: &mut Criterion) {
+    let text = "This is a much longer text intended for the large benchmark of the encode function. It spans several sentences and aims to represent a more massive workload typical in extensive encoding operations. The complexity and length are intentionally higher to test the performance impact on larger inputs.".repeat(10_000);
+    let mut group = c.benchmark_group("encode_huge");
+    group.bench_function("sequential", |b| {
+        b.iter(|| encode(black_box(&text)));
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| stego_wps::encode_parallel(black_box(&text)));
+    });
+    group.finish();
+}
+
+#[cfg(not(feature = "rayon"))]
 criterion_group!(benches, bench_encode_small, bench_encode_medium, bench_encode_large);
+#[cfg(feature = "rayon")]
+criterion_group!(
+    benches,
+    bench_encode_small,
+    bench_encode_medium,
+    bench_encode_large,
+    bench_encode_parallel
+);
 criterion_main!(benches);
 
@@ -1,33 +1,341 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::Range;
+use core::str::{CharIndices, FromStr};
+#[cfg(feature = "logging")]
 use log::{debug, warn};
-use std::collections::HashMap;
-use thiserror::Error;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::io::Read;
+
+/// Stand-ins for the `log` macros when the `logging` feature is disabled. The arguments are still
+/// type-checked, but never evaluated.
+#[cfg(not(feature = "logging"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+#[cfg(not(feature = "logging"))]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum EncodingError {
-    #[error("input must be ASCII string")]
     NonAsciiInput,
-    #[error("no valid sentences found")]
     NoValidSentences,
+    EmptyInput,
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
 }
 
-#[derive(Error, Debug)]
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonAsciiInput => write!(f, "input must be ASCII string"),
+            Self::NoValidSentences => write!(f, "no valid sentences found"),
+            Self::EmptyInput => write!(f, "input text is empty"),
+            #[cfg(feature = "std")]
+            Self::Io(kind) => write!(f, "failed to read input: {kind}"),
+        }
+    }
+}
+
+impl core::error::Error for EncodingError {}
+
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DecodingError {
-    #[error("character set cannot be empty")]
     EmptyCharacterSet,
-    #[error("invalid code: {0}")]
     InvalidCode(usize),
+    DuplicateCharacter(char),
+    ZeroCharsPerSentence,
+    CharsPerSentenceTooLarge,
+    ChecksumMismatch,
+    InvalidPermutation,
+    ZeroStreams,
+}
+
+impl fmt::Display for DecodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyCharacterSet => write!(f, "character set cannot be empty"),
+            Self::InvalidCode(code) => write!(f, "invalid code: {code}"),
+            Self::DuplicateCharacter(c) => write!(f, "duplicate character in character set: '{c}'"),
+            Self::ZeroCharsPerSentence => write!(f, "chars per sentence must be at least 1"),
+            Self::CharsPerSentenceTooLarge => {
+                write!(f, "chars per sentence is too large for the character set")
+            }
+            Self::ChecksumMismatch => write!(f, "checksum does not match the encoded message"),
+            Self::ZeroStreams => write!(f, "number of streams must be at least 1"),
+            Self::InvalidPermutation => {
+                write!(f, "permutation is not a bijection over the character set")
+            }
+        }
+    }
 }
 
-#[derive(Error, Debug)]
+impl core::error::Error for DecodingError {}
+
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum CompareError {
-    #[error("Value out of range")]
     ValueOutOfRange,
-    #[error("Error encoding cover text: {0}")]
-    EncodingError(#[from] EncodingError),
-    #[error("Character '{0}' not found in character set")]
+    EncodingError(EncodingError),
+    DecodingError(DecodingError),
     CharacterNotFound(char),
+    InsufficientWords {
+        /// The 0-based index of the sentence. The error message counts sentences from 1.
+        sentence: usize,
+        available: usize,
+        requested: usize,
+    },
+    InvalidFillerWord(String),
+    EmptyWordPool,
+    InsufficientCapacity {
+        have: usize,
+        need: usize,
+    },
+    SentenceTooLong {
+        /// The 0-based index of the sentence. The error message counts sentences from 1.
+        sentence: usize,
+        words: usize,
+        max: usize,
+    },
+    ZeroTargetWords,
+    MismatchedSecretLengths,
+    NoCoverTexts,
+    SecretTooLong {
+        length: usize,
+        max: usize,
+    },
+}
+
+impl fmt::Display for CompareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ValueOutOfRange => write!(f, "Value out of range"),
+            Self::EncodingError(e) => write!(f, "Error encoding cover text: {e}"),
+            Self::DecodingError(e) => write!(f, "Error decoding cover text: {e}"),
+            Self::CharacterNotFound(c) => write!(f, "Character '{c}' not found in character set"),
+            Self::InsufficientWords {
+                sentence,
+                available,
+                requested,
+            } => write!(
+                f,
+                "Sentence {} has {available} words, cannot remove {requested}",
+                sentence + 1
+            ),
+            Self::InvalidFillerWord(word) => write!(f, "Invalid filler word: '{word}'"),
+            Self::EmptyWordPool => write!(f, "Word pool cannot be empty"),
+            Self::InsufficientCapacity { have, need } => write!(
+                f,
+                "Cover text has {have} sentences, but the secret message needs {need}"
+            ),
+            Self::SentenceTooLong {
+                sentence,
+                words,
+                max,
+            } => write!(
+                f,
+                "Sentence {} needs {words} words, more than the maximum of {max}",
+                sentence + 1
+            ),
+            Self::ZeroTargetWords => write!(f, "Target word count must be at least 1"),
+            Self::MismatchedSecretLengths => {
+                write!(f, "Interleaved secret messages must have the same length")
+            }
+            Self::NoCoverTexts => write!(f, "At least one cover text is required"),
+            Self::SecretTooLong { length, max } => write!(
+                f,
+                "Secret message has {length} characters, more than the maximum of {max}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for CompareError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::EncodingError(e) => Some(e),
+            Self::DecodingError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExtractError {
+    EncodingError(EncodingError),
+    DecodingError(DecodingError),
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EncodingError(e) => write!(f, "Error encoding cover text: {e}"),
+            Self::DecodingError(e) => write!(f, "Error decoding cover text: {e}"),
+        }
+    }
+}
+
+impl core::error::Error for ExtractError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::EncodingError(e) => Some(e),
+            Self::DecodingError(e) => Some(e),
+        }
+    }
+}
+
+impl From<EncodingError> for ExtractError {
+    fn from(error: EncodingError) -> Self {
+        Self::EncodingError(error)
+    }
+}
+
+impl From<DecodingError> for ExtractError {
+    fn from(error: DecodingError) -> Self {
+        Self::DecodingError(error)
+    }
+}
+
+impl From<EncodingError> for CompareError {
+    fn from(error: EncodingError) -> Self {
+        Self::EncodingError(error)
+    }
+}
+
+impl From<DecodingError> for CompareError {
+    fn from(error: DecodingError) -> Self {
+        Self::DecodingError(error)
+    }
+}
+
+/// The word counts per sentence produced by [`encode`].
+///
+/// This newtype gives encoded values a stable representation to persist and pass around. With the `serde`
+/// feature enabled it implements `Serialize` and `Deserialize`, serializing transparently as a sequence of
+/// counts. Convert from the output of [`encode`] with `.into()`.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode, Encoded};
+///
+/// let encoded: Encoded = encode("Hello world. How are you?").expect("Failed to encode").into();
+/// assert_eq!(encoded, Encoded(vec![2, 3]));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Encoded(pub Vec<usize>);
+
+impl From<Vec<usize>> for Encoded {
+    fn from(counts: Vec<usize>) -> Self {
+        Self(counts)
+    }
+}
+
+impl From<Encoded> for Vec<usize> {
+    fn from(encoded: Encoded) -> Self {
+        encoded.0
+    }
+}
+
+impl AsRef<[usize]> for Encoded {
+    fn as_ref(&self) -> &[usize] {
+        &self.0
+    }
 }
 
+impl Encoded {
+    /// Decodes the word counts into a string using a specified character set, like [`decode`].
+    ///
+    /// # Errors
+    /// This function returns the same errors as [`decode`].
+    ///
+    /// # Examples
+    /// ```
+    /// use stego_wps::Encoded;
+    ///
+    /// let encoded = Encoded(vec![8, 9]);
+    /// assert_eq!(encoded.decode("ABCDEFGHIJKLMNOPQRSTUVWXYZ").expect("Failed to decode"), "HI");
+    /// ```
+    pub fn decode(&self, character_set: &str) -> Result<String, DecodingError> {
+        decode(&self.0, character_set)
+    }
+}
+
+/// The word counts of a text together with the message they decode to, as returned by [`encode_and_decode`].
+///
+/// Its `Display` implementation prints both, such as `[8, 9] => "HI"`, which is handy for command-line tools.
+///
+/// # Examples
+/// ```
+/// use stego_wps::Decoded;
+///
+/// let decoded = Decoded { counts: vec![8, 9], text: "HI".to_string() };
+/// assert_eq!(decoded.to_string(), r#"[8, 9] => "HI""#);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Decoded {
+    /// The word count of each sentence.
+    pub counts: Vec<usize>,
+    /// The message decoded from `counts`.
+    pub text: String,
+}
+
+impl Decoded {
+    /// Returns the [`printable_ratio`] of the decoded message.
+    ///
+    /// # Examples
+    /// ```
+    /// use stego_wps::encode_and_decode;
+    ///
+    /// let decoded = encode_and_decode("Hello world. How are you?", "ABC").expect("Failed to decode");
+    /// assert!((decoded.printable_ratio() - 1.0).abs() < f64::EPSILON);
+    /// ```
+    #[must_use]
+    pub fn printable_ratio(&self) -> f64 {
+        printable_ratio(&self.text)
+    }
+}
+
+impl fmt::Display for Decoded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} => {:?}", self.counts, self.text)
+    }
+}
+
+/// The sentence terminators used by [`encode`]: `.`, `!` and `?`.
+pub const DEFAULT_TERMINATORS: &[char] = &['.', '!', '?'];
+
+/// Common abbreviations whose trailing period does not end a sentence, for use with [`encode_with_abbreviations`].
+pub const DEFAULT_ABBREVIATIONS: &[&str] = &["Mr", "Mrs", "Ms", "Dr", "St"];
+
 /// Encodes a given text into a vector of word counts per sentence.
 ///
 /// This function performs text-based steganography using the Words Per Sentence (WPS) method.
@@ -35,6 +343,10 @@ pub enum CompareError {
 /// and returns a vector of these counts. The function ensures that the input text is ASCII-encoded
 /// and contains valid sentences.
 ///
+/// Sentences are terminated by any of the [`DEFAULT_TERMINATORS`]. Use [`encode_with_terminators`]
+/// to split on a different set of characters. A word is a whitespace-separated token with at least one letter or
+/// digit, so a standalone dash is not counted, see [`WordPolicy`].
+///
 /// # Arguments
 /// * `txt` - A string slice (`&str`) representing the text to be encoded.
 ///
@@ -42,11 +354,13 @@ pub enum CompareError {
 /// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
 /// * `Err(EncodingError)` - An `EncodingError` in case of:
 ///   - Non-ASCII input (`EncodingError::NonAsciiInput`).
+///   - Empty or whitespace-only input text (`EncodingError::EmptyInput`).
 ///   - Input text with no valid sentences (`EncodingError::NoValidSentences`).
 ///
 /// # Errors
 /// This function returns an error in the following cases:
 /// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
 /// - If the input text does not contain any valid sentences (i.e., no words or only punctuation),
 ///   an `EncodingError::NoValidSentences` error is returned.
 ///
@@ -61,277 +375,7297 @@ pub enum CompareError {
 ///     Err(e) => match e {
 ///         EncodingError::NonAsciiInput => println!("Input text must be ASCII"),
 ///         EncodingError::NoValidSentences => println!("No valid sentences found"),
+///         e => println!("Encoding failed: {}", e),
 ///     },
 /// }
 /// ```
 pub fn encode(txt: &str) -> Result<Vec<usize>, EncodingError> {
-    if !txt.is_ascii() {
-        warn!("Non-ASCII string encountered");
-        return Err(EncodingError::NonAsciiInput);
-    }
+    encode_with_delimiters(txt, DEFAULT_TERMINATORS)
+}
 
-    let encoded: Vec<usize> = txt
-        .split(|c: char| ['.', '!', '?'].contains(&c))
-        .map(|s| s.split_whitespace().count())
-        .filter(|&count| count > 0)
-        .collect();
+/// Encodes a given text into its [`Encoded`] word counts per sentence.
+///
+/// This behaves exactly like [`encode`], wrapping the result in [`Encoded`] so it can be stored, serialized
+/// with the `serde` feature and decoded again with [`Encoded::decode`].
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Encoded)` - The word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns the same errors as [`encode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode_wrapped, Encoded};
+///
+/// let encoded = encode_wrapped("Hello world. How are you?").expect("Failed to encode");
+/// assert_eq!(encoded, Encoded(vec![2, 3]));
+/// assert_eq!(encoded.decode("ABC").expect("Failed to decode"), "BC");
+/// ```
+pub fn encode_wrapped(txt: &str) -> Result<Encoded, EncodingError> {
+    encode(txt).map(Encoded)
+}
 
-    if encoded.is_empty() {
-        warn!("No valid sentences found in the input text");
-        return Err(EncodingError::NoValidSentences);
+/// Returns the fraction of characters of a decoded message that are letters or spaces.
+///
+/// Decoding never checks that the cover text actually hides a message: any text decodes to something. A real
+/// message is mostly letters and spaces, while decoding an ordinary text with a character set full of digits and
+/// punctuation tends to produce garbage, so tools can compare this ratio against a threshold. Letters include
+/// non-ASCII ones.
+///
+/// # Arguments
+/// * `decoded` - A string slice (`&str`) representing the decoded message.
+///
+/// # Returns
+/// * `f64` - The ratio, from `0.0` to `1.0`. An empty string has a ratio of `0.0`.
+///
+/// # Examples
+/// ```
+/// use stego_wps::printable_ratio;
+///
+/// assert!((printable_ratio("HELLO WORLD") - 1.0).abs() < f64::EPSILON);
+/// assert!((printable_ratio("H3#!") - 0.25).abs() < f64::EPSILON);
+/// assert!(printable_ratio("").abs() < f64::EPSILON);
+/// ```
+// Messages long enough to lose precision as an `f64` are not a realistic concern.
+#[allow(clippy::cast_precision_loss)]
+#[must_use]
+pub fn printable_ratio(decoded: &str) -> f64 {
+    let total = decoded.chars().count();
+    if total == 0 {
+        return 0.0;
     }
 
-    debug!("Encoded text: {:?}", encoded);
-    Ok(encoded)
+    let printable = decoded
+        .chars()
+        .filter(|&c| c.is_alphabetic() || c == ' ')
+        .count();
+    printable as f64 / total as f64
 }
 
-/// Decodes a vector of word counts per sentence into a string using a specified character set.
+/// Extracts the secret message hidden in a cover text.
 ///
-/// This function is part of a text-based steganography system using the Words Per Sentence (WPS) method.
-/// It decodes a sequence of word counts (represented as a vector of `usize`) back into a string based on a given
-/// character set. Each word count is used to find a corresponding character in the character set.
-/// The function ensures that the character set is not empty and that each word count is valid within the context of the character set.
+/// This is the most common workflow in a single call: [`encode`] the cover text into its word counts, then
+/// [`decode`] them with the given character set. Use [`encode_and_decode`] to keep the word counts as well, and
+/// [`extract_with_ratio`] to also get the [`printable_ratio`] of the result, to judge whether it looks like a real
+/// message.
 ///
 /// # Arguments
-/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `cover_text` - A string slice (`&str`) representing the cover text hiding the message.
 /// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
 ///
 /// # Returns
-/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
-/// * `Err(DecodingError)` - A `DecodingError` in case of:
-///   - Empty character set (`DecodingError::EmptyCharacterSet`).
-///   - Invalid word count that does not correspond to any character in the set (`DecodingError::InvalidCode`).
+/// * `Ok(String)` - The secret message if extraction is successful.
+/// * `Err(ExtractError)` - An error if encoding or decoding fails.
 ///
 /// # Errors
-/// This function returns an error in the following cases:
-/// - If the character set provided is empty, an `DecodingError::EmptyCharacterSet` error is returned.
-/// - If a word count in the `encoded` vector does not correspond to a character in the character set (i.e., out of range),
-///   a `DecodingError::InvalidCode` error is returned with the invalid count.
+/// This function returns `ExtractError::EncodingError` with the errors of [`encode`] and
+/// `ExtractError::DecodingError` with the errors of [`decode`].
 ///
 /// # Examples
 /// ```
-/// use stego_wps::decode;
-/// use stego_wps::DecodingError;
+/// use stego_wps::extract;
 ///
-/// let encoded = vec![3, 5, 1];
-/// let character_set = "ABCDE";
-/// match decode(&encoded, character_set) {
-///     Ok(decoded) => println!("Decoded string: {}", decoded),
-///     Err(e) => match e {
-///         DecodingError::EmptyCharacterSet => println!("Character set cannot be empty"),
-///         DecodingError::InvalidCode(code) => println!("Invalid code: {}", code),
-///     },
-/// }
+/// let cover_text = "Hello world. How are you?";
+/// assert_eq!(extract(cover_text, "ABC").expect("Failed to extract"), "BC");
 /// ```
-pub fn decode(encoded: &[usize], character_set: &str) -> Result<String, DecodingError> {
-    if character_set.is_empty() {
-        warn!("Character set is empty");
-        return Err(DecodingError::EmptyCharacterSet);
-    }
-
-    let charset_len = character_set.len();
-    let decoded: Result<String, _> = encoded
-        .iter()
-        .filter(|&&code| code != 0)
-        .map(|&code| {
-            character_set
-                .chars()
-                .nth((code - 1) % charset_len)
-                .ok_or(DecodingError::InvalidCode(code))
-        })
-        .collect();
+pub fn extract(cover_text: &str, character_set: &str) -> Result<String, ExtractError> {
+    let encoded = encode(cover_text)?;
+    Ok(decode(&encoded, character_set)?)
+}
 
-    match decoded {
-        Ok(d) => {
-            debug!("Decoded string: {}", d);
-            Ok(d)
-        }
-        Err(e) => {
-            warn!("Decoding error: {:?}", e);
-            Err(e)
-        }
-    }
+/// A message extracted from a cover text together with its [`printable_ratio`], as returned by
+/// [`extract_with_ratio`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Extracted {
+    /// The message decoded from the cover text.
+    pub message: String,
+    /// The fraction of characters of `message` that are letters or spaces, from `0.0` to `1.0`.
+    pub printable_ratio: f64,
 }
 
-/// Compares a secret message with a cover text to calculate the necessary changes in word count per sentence to encode the message.
+/// Extracts the secret message hidden in a cover text, along with how plausible it looks.
 ///
-/// This function is a part of a text-based steganography system using the Words Per Sentence (WPS) method.
-/// It calculates the differences in word count needed for each sentence of the cover text to match the encoded form of the secret message.
-/// The function returns a vector where each element represents the change in the number of words required for a corresponding sentence
-/// in the cover text to encode a character of the secret message using the provided character set.
+/// This behaves exactly like [`extract`], but also returns the [`printable_ratio`] of the message. Any text
+/// decodes to something, so tools can compare the ratio against a threshold to tell a real message from the
+/// garbage an ordinary text decodes to.
 ///
 /// # Arguments
-/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
-/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
-/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `cover_text` - A string slice (`&str`) representing the cover text hiding the message.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
 ///
 /// # Returns
-/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
-///    Positive values indicate additional words needed, while negative values indicate words to be removed.
-/// * `Err(CompareError)` - An error if there is a problem in the comparison process, such as value out of range, encoding errors, or missing characters in the character set.
+/// * `Ok(Extracted)` - The secret message and its printable ratio if extraction is successful.
+/// * `Err(ExtractError)` - An error if encoding or decoding fails.
 ///
 /// # Errors
-/// This function returns an error if:
-/// - The cover text cannot be successfully encoded (`EncodingError`).
-/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
-/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+/// This function returns the same errors as [`extract`].
 ///
 /// # Examples
 /// ```
-/// use stego_wps::compare;
-/// use stego_wps::CompareError;
+/// use stego_wps::extract_with_ratio;
 ///
-/// let secret_message = "HELLO";
-/// let cover_text = "This is a sentence. Another one here.";
-/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-/// match compare(secret_message, cover_text, character_set) {
-///     Ok(changes) => println!("Changes needed: {:?}", changes),
-///     Err(e) => eprintln!("Comparison failed: {:?}", e),
-/// }
+/// let extracted = extract_with_ratio("Hello world. How are you?", "ABC").expect("Failed to extract");
+/// assert_eq!(extracted.message, "BC");
+/// assert!(extracted.printable_ratio > 0.9);
 /// ```
-pub fn compare(
-    secret_message: &str,
+pub fn extract_with_ratio(
     cover_text: &str,
     character_set: &str,
-) -> Result<Vec<isize>, CompareError> {
-    if secret_message.is_empty() {
-        return Ok(vec![]);
-    }
+) -> Result<Extracted, ExtractError> {
+    let message = extract(cover_text, character_set)?;
+    Ok(Extracted {
+        printable_ratio: printable_ratio(&message),
+        message,
+    })
+}
 
-    let charset_map: HashMap<char, isize> = character_set
-        .chars()
-        .enumerate()
-        .map(|(i, c)| {
-            isize::try_from(i).map_or(Err(CompareError::ValueOutOfRange), |val| Ok((c, val + 1)))
-        })
-        .collect::<Result<_, _>>()?;
+/// Encodes a given text and decodes its word counts in one step, keeping both.
+///
+/// This runs [`encode`] followed by [`decode`] and returns the word counts alongside the decoded message, so
+/// callers that display or log both don't need to thread them around separately.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// * `Ok(Decoded)` - The word counts and the decoded message.
+/// * `Err(CompareError)` - An error if encoding or decoding fails.
+///
+/// # Errors
+/// This function returns `CompareError::EncodingError` with the errors of [`encode`] and
+/// `CompareError::DecodingError` with the errors of [`decode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_and_decode;
+///
+/// let decoded = encode_and_decode("Hello world. How are you?", "ABC").expect("Failed to decode");
+/// assert_eq!(decoded.counts, vec![2, 3]);
+/// assert_eq!(decoded.text, "BC");
+/// println!("{decoded}");
+/// ```
+pub fn encode_and_decode(txt: &str, character_set: &str) -> Result<Decoded, CompareError> {
+    let counts = encode(txt)?;
+    let message = decode(&counts, character_set)?;
+    Ok(Decoded {
+        counts,
+        text: message,
+    })
+}
 
-    let cover_encoded = encode(cover_text).map_err(CompareError::EncodingError)?;
+/// Which whitespace-separated tokens [`encode_with`] counts as words.
+///
+/// Hyphenated words and contractions such as `state-of-the-art` or `don't` are single words under every policy.
+/// The policies only differ for tokens without letters or digits, such as the dash in `end -- start` or a
+/// standalone ellipsis. A sentence left without any word is dropped, like an empty sentence.
+///
+/// The default is `SkipPunctuation`, which every encoder, [`apply_changes`] and [`pad_sentence`] follow. Versions
+/// before 1.2 counted every token, so use `All` to decode stego texts made with them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WordPolicy {
+    /// Count every token, including standalone punctuation, like versions before 1.2 did.
+    All,
+    /// Only count tokens containing at least one letter or digit, so `"Hello -- world."` has 2 words. This is
+    /// what [`encode`] does.
+    #[default]
+    SkipPunctuation,
+    /// Only count tokens containing at least one letter, so numbers such as `42` or `3,000` are skipped as well,
+    /// and `"We saw 5 cats."` has 3 words.
+    #[doc(alias = "AlphaOnly")]
+    Alphabetic,
+}
 
-    let secret_positions = secret_message
-        .chars()
-        .map(|c| {
-            charset_map
-                .get(&c)
-                .copied()
-                .ok_or_else(|| CompareError::CharacterNotFound(c))
-        })
-        .collect::<Result<Vec<isize>, _>>()?;
+/// How [`encode_with`] counts the words of a whitespace-separated token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WordCounting {
+    /// Every token is one word. This is what [`encode`] does.
+    #[default]
+    Whitespace,
+    /// Count words by Unicode word boundaries, for scripts that don't separate words with spaces. Each Han,
+    /// Hiragana or Katakana character is a word, so `"我爱 Rust"` has 3 words, and every run of letters and digits
+    /// is a word, so punctuation doesn't count and a hyphenated compound counts once per part. Contractions such
+    /// as `don't` stay one word. Right-to-left scripts such as Arabic and Hebrew separate words with spaces and
+    /// count like any other. Since this only makes sense for non-ASCII texts, it also lifts the ASCII requirement,
+    /// as if `allow_non_ascii` were set. Sentences are still split on the configured `terminators`, so add
+    /// full-width terminators such as `。` to split Chinese or Japanese text. Scripts such as Thai, whose word
+    /// boundaries need a dictionary, are not segmented.
+    Unicode,
+}
 
-    let mut changes = vec![0; cover_encoded.len()];
-    for (i, &pos) in secret_positions.iter().enumerate() {
-        if i < cover_encoded.len() {
-            changes[i] = pos
-                - isize::try_from(cover_encoded[i]).map_err(|_| CompareError::ValueOutOfRange)?;
-        } else {
-            changes.push(pos);
+/// Options controlling how [`encode_with`] splits a text into sentences and counts its words.
+///
+/// `EncodeOptions::default()` matches the behavior of [`encode`] exactly. The specialized encode functions,
+/// such as [`encode_with_terminators`] or [`encode_with_abbreviations`], are thin wrappers that set a single
+/// option; use this struct directly to combine several of them.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode_with, EncodeOptions, DEFAULT_ABBREVIATIONS};
+///
+/// let options = EncodeOptions {
+///     abbreviations: DEFAULT_ABBREVIATIONS.iter().map(ToString::to_string).collect(),
+///     keep_decimal_numbers: true,
+///     ..EncodeOptions::default()
+/// };
+/// let encoded = encode_with("Dr. Who paid 2.50 for tea. Fine!", &options).expect("Failed to encode");
+/// assert_eq!(encoded, vec![6, 1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct EncodeOptions {
+    /// The characters that end a sentence. Defaults to [`DEFAULT_TERMINATORS`]. If empty, the whole text is a
+    /// single sentence.
+    pub terminators: Vec<char>,
+    /// Also end a sentence at every line break, for poems, lists and other texts with one unit per line. Line
+    /// breaks may be `\n`, `\r\n` or a lone `\r`, and a `\r\n` pair is a single boundary.
+    pub treat_newlines_as_delimiters: bool,
+    /// Accept non-ASCII input instead of failing with `EncodingError::NonAsciiInput`.
+    pub allow_non_ascii: bool,
+    /// Replace Unicode whitespace, such as the non-breaking spaces found in text pasted from PDFs, with ASCII
+    /// spaces and remove zero-width characters, such as U+200B, before encoding. This happens before the ASCII
+    /// check, so a text whose only non-ASCII characters are whitespace encodes without `allow_non_ascii`, and
+    /// zero-width characters never glue words together or count as words of their own.
+    pub allow_unicode_whitespace: bool,
+    /// Abbreviations, without their trailing period, whose period does not end a sentence. They are matched
+    /// case-insensitively.
+    pub abbreviations: Vec<String>,
+    /// Don't end a sentence at a period flanked by ASCII digits on both sides, such as the one in `3.14`.
+    pub keep_decimal_numbers: bool,
+    /// Count hyphenated compounds such as `well-known` as one word per part rather than a single word.
+    /// This only affects the counts, not how sentences are split.
+    pub split_hyphens: bool,
+    /// Which whitespace-separated tokens count as words. Defaults to `WordPolicy::SkipPunctuation`.
+    pub word_policy: WordPolicy,
+    /// How the tokens left by `word_policy` are counted. Defaults to `WordCounting::Whitespace`.
+    pub word_counting: WordCounting,
+    /// Treat a run of consecutive terminators, such as the ellipsis in `Wait... what?`, as a single sentence
+    /// boundary instead of one boundary per character. Unless `keep_empty_sentences` is set, this never changes
+    /// the word counts, since the empty sentences between the terminators of a run are filtered out anyway, but
+    /// it keeps them from being split off in the first place.
+    pub collapse_terminator_runs: bool,
+    /// Emit a word count of `0` for sentences without any word, such as the one between the periods of
+    /// `Hi. . There.`, instead of dropping them. This preserves the position of every sentence, but [`decode`]
+    /// skips zero codes, so the counts then need [`decode_with_offset`] with an offset of `0` to be meaningful.
+    /// A text whose sentences are all empty still fails with `EncodingError::NoValidSentences`, or
+    /// `EncodingError::EmptyInput` if it is blank.
+    pub keep_empty_sentences: bool,
+    /// Count the trailing fragment of a text that does not end in a terminator, such as `d e` in `A b c. d e`, as
+    /// a sentence of its own. Defaults to `true`; when unset, the fragment is dropped, which suits texts that were
+    /// cut off mid-sentence.
+    pub include_unterminated: bool,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self {
+            terminators: DEFAULT_TERMINATORS.to_vec(),
+            treat_newlines_as_delimiters: false,
+            allow_non_ascii: false,
+            abbreviations: Vec::new(),
+            keep_decimal_numbers: false,
+            split_hyphens: false,
+            word_policy: WordPolicy::SkipPunctuation,
+            word_counting: WordCounting::Whitespace,
+            collapse_terminator_runs: false,
+            keep_empty_sentences: false,
+            allow_unicode_whitespace: false,
+            include_unterminated: true,
         }
     }
+}
 
-    for i in secret_positions.len()..cover_encoded.len() {
-        changes[i] =
-            -isize::try_from(cover_encoded[i]).map_err(|_| CompareError::ValueOutOfRange)?;
+/// A builder for configuring how a text is encoded in one place.
+///
+/// Each method sets one field of the underlying [`EncodeOptions`] and returns the builder, so the options can be
+/// chained before calling [`Encoder::encode`]. `Encoder::new()` encodes exactly like [`encode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::Encoder;
+///
+/// let encoded = Encoder::new()
+///     .delimiters(&['.', '!'])
+///     .abbreviations(&["Dr"])
+///     .allow_non_ascii(true)
+///     .encode("Dr. Müller is here! Is he? Yes.")
+///     .expect("Failed to encode");
+/// assert_eq!(encoded, vec![4, 3]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Encoder {
+    options: EncodeOptions,
+}
+
+impl Encoder {
+    /// Creates a builder with the default [`EncodeOptions`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    Ok(changes)
-}
+    /// Sets the characters that end a sentence, see [`EncodeOptions::terminators`].
+    #[must_use]
+    pub fn delimiters(mut self, delimiters: &[char]) -> Self {
+        self.options.terminators = delimiters.to_vec();
+        self
+    }
 
-#[cfg(test)]
+    /// Sets whether line breaks end a sentence, see [`EncodeOptions::treat_newlines_as_delimiters`].
+    #[must_use]
+    pub const fn treat_newlines_as_delimiters(mut self, treat: bool) -> Self {
+        self.options.treat_newlines_as_delimiters = treat;
+        self
+    }
+
+    /// Sets the abbreviations whose period does not end a sentence, see [`EncodeOptions::abbreviations`].
+    #[must_use]
+    pub fn abbreviations(mut self, abbreviations: &[&str]) -> Self {
+        self.options.abbreviations = abbreviations.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Sets whether non-ASCII input is accepted, see [`EncodeOptions::allow_non_ascii`].
+    #[must_use]
+    pub const fn allow_non_ascii(mut self, allow: bool) -> Self {
+        self.options.allow_non_ascii = allow;
+        self
+    }
+
+    /// Sets whether decimal points split sentences, see [`EncodeOptions::keep_decimal_numbers`].
+    #[must_use]
+    pub const fn keep_decimal_numbers(mut self, keep: bool) -> Self {
+        self.options.keep_decimal_numbers = keep;
+        self
+    }
+
+    /// Sets whether hyphenated compounds count once per part, see [`EncodeOptions::split_hyphens`].
+    #[must_use]
+    pub const fn split_hyphens(mut self, split: bool) -> Self {
+        self.options.split_hyphens = split;
+        self
+    }
+
+    /// Sets which tokens count as words, see [`EncodeOptions::word_policy`].
+    #[must_use]
+    pub const fn word_policy(mut self, policy: WordPolicy) -> Self {
+        self.options.word_policy = policy;
+        self
+    }
+
+    /// Sets how tokens are counted as words, see [`EncodeOptions::word_counting`].
+    #[must_use]
+    pub const fn word_counting(mut self, counting: WordCounting) -> Self {
+        self.options.word_counting = counting;
+        self
+    }
+
+    /// Sets whether runs of terminators form one boundary, see [`EncodeOptions::collapse_terminator_runs`].
+    #[must_use]
+    pub const fn collapse_terminator_runs(mut self, collapse: bool) -> Self {
+        self.options.collapse_terminator_runs = collapse;
+        self
+    }
+
+    /// Sets whether empty sentences yield a count of `0`, see [`EncodeOptions::keep_empty_sentences`].
+    #[must_use]
+    pub const fn keep_empty_sentences(mut self, keep: bool) -> Self {
+        self.options.keep_empty_sentences = keep;
+        self
+    }
+
+    /// Sets whether Unicode whitespace is normalized first, see [`EncodeOptions::allow_unicode_whitespace`].
+    #[must_use]
+    pub const fn allow_unicode_whitespace(mut self, allow: bool) -> Self {
+        self.options.allow_unicode_whitespace = allow;
+        self
+    }
+
+    /// Sets whether a trailing unterminated fragment counts, see [`EncodeOptions::include_unterminated`].
+    #[must_use]
+    pub const fn include_unterminated(mut self, include: bool) -> Self {
+        self.options.include_unterminated = include;
+        self
+    }
+
+    /// Returns the [`EncodeOptions`] configured so far.
+    #[must_use]
+    pub const fn options(&self) -> &EncodeOptions {
+        &self.options
+    }
+
+    /// Encodes a given text into a vector of word counts per sentence, using the configured options.
+    ///
+    /// # Errors
+    /// This function returns the same errors as [`encode_with`].
+    pub fn encode(&self, txt: &str) -> Result<Vec<usize>, EncodingError> {
+        encode_with(txt, &self.options)
+    }
+}
+
+impl From<EncodeOptions> for Encoder {
+    fn from(options: EncodeOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes a given text into a vector of word counts per sentence, using the given [`EncodeOptions`].
+///
+/// With default options this behaves exactly like [`encode`]. Each option adjusts how the text is validated
+/// and split into sentences, see [`EncodeOptions`] for the available settings.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+/// * `options` - The [`EncodeOptions`] to encode with.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded and `allow_non_ascii` is not set, an `EncodingError::NonAsciiInput`
+///   error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode_with, EncodeOptions};
+///
+/// let text = "Pi is 3.14 and e is 2.71 roughly.";
+/// let options = EncodeOptions {
+///     keep_decimal_numbers: true,
+///     ..EncodeOptions::default()
+/// };
+/// assert_eq!(encode_with(text, &options).expect("Failed to encode"), vec![8]);
+/// ```
+pub fn encode_with(txt: &str, options: &EncodeOptions) -> Result<Vec<usize>, EncodingError> {
+    let normalized;
+    let txt = if options.allow_unicode_whitespace {
+        normalized = normalize_unicode_whitespace(txt);
+        normalized.as_str()
+    } else {
+        txt
+    };
+
+    check_ascii(
+        txt,
+        options.allow_non_ascii || options.word_counting != WordCounting::Whitespace,
+    )?;
+
+    let encoded: Vec<usize> = sentence_ranges(txt, options)
+        .filter_map(|(range, terminator)| sentence_word_count(txt, range, terminator, options))
+        .filter(|&count| options.keep_empty_sentences || count > 0)
+        .collect();
+
+    if encoded.iter().all(|&count| count == 0) {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded text: {encoded:?}");
+    Ok(encoded)
+}
+
+/// Counts the words of the sentence of `txt` at `range`, or returns `None` if the sentence is an unterminated
+/// fragment that `options` leaves out.
+fn sentence_word_count(
+    txt: &str,
+    range: Range<usize>,
+    terminator: Option<char>,
+    options: &EncodeOptions,
+) -> Option<usize> {
+    (options.include_unterminated || terminator.is_some())
+        .then(|| count_words(&txt[range], options))
+}
+
+/// Returns the error for a text without any valid sentence: `EmptyInput` if the text is blank, that is empty or
+/// only whitespace, and `NoValidSentences` otherwise.
+fn no_sentences_error(blank: bool) -> EncodingError {
+    if blank {
+        warn!("Input text is empty");
+        EncodingError::EmptyInput
+    } else {
+        warn!("No valid sentences found in the input text");
+        EncodingError::NoValidSentences
+    }
+}
+
+/// Fails with `EncodingError::NonAsciiInput` if `txt` is not ASCII, unless `allow_non_ascii` is set.
+fn check_ascii(txt: &str, allow_non_ascii: bool) -> Result<(), EncodingError> {
+    if allow_non_ascii || txt.is_ascii() {
+        Ok(())
+    } else {
+        warn!("Non-ASCII string encountered");
+        Err(EncodingError::NonAsciiInput)
+    }
+}
+
+/// Encodes a given text into a vector of word counts per sentence using a custom set of terminators.
+///
+/// This behaves exactly like [`encode`], except that sentences are split on the characters in
+/// `terminators` instead of the [`DEFAULT_TERMINATORS`]. This is useful for texts that delimit
+/// logical clauses with characters such as `;` or `:`. Empty sentences are filtered out as usual.
+///
+/// If `terminators` is empty, the whole text is treated as a single sentence.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+/// * `terminators` - A slice of characters that end a sentence.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_with_terminators;
+///
+/// let text = "first clause here; second clause; third";
+/// let encoded = encode_with_terminators(text, &[';']).expect("Failed to encode");
+/// assert_eq!(encoded, vec![3, 2, 1]);
+/// ```
+pub fn encode_with_terminators(
+    txt: &str,
+    terminators: &[char],
+) -> Result<Vec<usize>, EncodingError> {
+    let options = EncodeOptions {
+        terminators: terminators.to_vec(),
+        ..EncodeOptions::default()
+    };
+    encode_with(txt, &options)
+}
+
+/// Encodes a given text into a vector of word counts per sentence, ending sentences at `delimiters`.
+///
+/// [`encode`] is this function with the [`DEFAULT_TERMINATORS`], so a text with semicolons, line breaks or
+/// language-specific punctuation such as the Devanagari `।` only needs a different delimiter set. It behaves
+/// exactly like [`encode_with_terminators`].
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+/// * `delimiters` - A slice of characters that end a sentence.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns the same errors as [`encode_with_terminators`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode, encode_with_delimiters, DEFAULT_TERMINATORS};
+///
+/// assert_eq!(encode_with_delimiters("a b; c d e;", &[';']).expect("Failed to encode"), vec![2, 3]);
+/// let text = "Hello world. How are you?";
+/// assert_eq!(encode_with_delimiters(text, DEFAULT_TERMINATORS), encode(text));
+/// ```
+pub fn encode_with_delimiters(txt: &str, delimiters: &[char]) -> Result<Vec<usize>, EncodingError> {
+    encode_with_terminators(txt, delimiters)
+}
+
+/// Encodes a given text that may contain non-ASCII characters into a vector of word counts per sentence.
+///
+/// This behaves like [`encode`] but skips the ASCII check, so accented or otherwise non-ASCII text can be
+/// used as a cover text. Words are still delimited by Unicode whitespace, so combining characters and
+/// accented letters never split a word: `"café résumé."` is a single sentence of 2 words.
+///
+/// [`encode`] stays ASCII-strict for backward compatibility.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError::EmptyInput` if the text is blank, or an
+///   `EncodingError::NoValidSentences` if it contains no valid sentences.
+///
+/// # Errors
+/// If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned. If it
+/// does not contain any valid sentences otherwise, an `EncodingError::NoValidSentences` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_unicode;
+///
+/// let encoded = encode_unicode("Café résumé. Voilà tout!").expect("Failed to encode");
+/// assert_eq!(encoded, vec![2, 2]);
+/// ```
+pub fn encode_unicode(txt: &str) -> Result<Vec<usize>, EncodingError> {
+    let options = EncodeOptions {
+        allow_non_ascii: true,
+        ..EncodeOptions::default()
+    };
+    encode_with(txt, &options)
+}
+
+/// Encodes a line-oriented text into a vector of word counts per line or sentence.
+///
+/// This behaves like [`encode`], but also ends a sentence at every line break, so texts with one clause per line
+/// and no terminal punctuation are not treated as a single giant sentence. Sentence terminators still split
+/// sentences within a line. Blank lines are filtered out like any other empty sentence. This is a shorthand for
+/// [`EncodeOptions::treat_newlines_as_delimiters`].
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per line or sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_line_oriented;
+///
+/// let text = "roses are red\nviolets are blue\n\nsugar is sweet";
+/// assert_eq!(encode_line_oriented(text).expect("Failed to encode"), vec![3, 3, 3]);
+/// ```
+pub fn encode_line_oriented(txt: &str) -> Result<Vec<usize>, EncodingError> {
+    let options = EncodeOptions {
+        treat_newlines_as_delimiters: true,
+        ..EncodeOptions::default()
+    };
+    encode_with(txt, &options)
+}
+
+/// Encodes a given text into a vector of word counts per sentence, without ending sentences at abbreviations.
+///
+/// This behaves like [`encode`], except that a period directly following one of the given `abbreviations`
+/// does not end the sentence, so `"Mr. Smith went home."` is a single sentence of 4 words rather than two.
+/// Abbreviations are matched case-insensitively against the whole run of letters and digits preceding the
+/// period, so `"Dr"` also matches `"DR."` and `"dr."`, but not `"Adr."`. A period ending an abbreviation at the
+/// end of the text does not end a sentence either, and the trailing fragment is counted like any unterminated
+/// sentence. [`DEFAULT_ABBREVIATIONS`] provides a set of common English titles.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+/// * `abbreviations` - A slice of abbreviations, without their trailing period.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode, encode_with_abbreviations, DEFAULT_ABBREVIATIONS};
+///
+/// let text = "Mr. Smith went home. He slept.";
+/// assert_eq!(encode(text).expect("Failed to encode"), vec![1, 3, 2]);
+/// let encoded = encode_with_abbreviations(text, DEFAULT_ABBREVIATIONS).expect("Failed to encode");
+/// assert_eq!(encoded, vec![4, 2]);
+/// ```
+pub fn encode_with_abbreviations(
+    txt: &str,
+    abbreviations: &[&str],
+) -> Result<Vec<usize>, EncodingError> {
+    let options = EncodeOptions {
+        abbreviations: abbreviations.iter().map(ToString::to_string).collect(),
+        ..EncodeOptions::default()
+    };
+    encode_with(txt, &options)
+}
+
+/// Encodes a given text into a vector of character counts per sentence.
+///
+/// This is the characters per sentence (CPS) counterpart of [`encode`]: sentences are split and validated
+/// exactly as in [`encode`], but each sentence is measured by its number of non-whitespace characters instead
+/// of its number of words. A sentence without any such character is filtered out, just like a sentence without
+/// words in [`encode`], so both functions always return the same number of counts for the same text.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of character counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode, encode_cps};
+///
+/// let text = "Hello world. How are you?";
+/// assert_eq!(encode(text).expect("Failed to encode"), vec![2, 3]);
+/// assert_eq!(encode_cps(text).expect("Failed to encode"), vec![10, 9]);
+/// ```
+pub fn encode_cps(txt: &str) -> Result<Vec<usize>, EncodingError> {
+    check_ascii(txt, false)?;
+
+    let encoded: Vec<usize> = sentence_ranges(txt, &EncodeOptions::default())
+        .map(|(range, _)| txt[range].chars().filter(|c| !c.is_whitespace()).count())
+        .filter(|&count| count > 0)
+        .collect();
+
+    if encoded.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded text: {encoded:?}");
+    Ok(encoded)
+}
+
+/// Encodes a given text into a vector of word counts per paragraph.
+///
+/// This is the words per paragraph (WPP) counterpart of [`encode`]: paragraphs are separated by one or more blank
+/// lines, and all words of a paragraph are counted together, regardless of sentence terminators. Lines holding
+/// only whitespace count as blank, and runs of blank lines separate just two paragraphs, so no empty paragraph is
+/// ever produced. Both `\n` and `\r\n` line endings are supported.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per paragraph if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no words.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any words, an `EncodingError::NoValidSentences` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_wpp;
+///
+/// let text = "First paragraph. Still the first.\n\nSecond one.\n \n\n\nThe third\nspans two lines.";
+/// assert_eq!(encode_wpp(text).expect("Failed to encode"), vec![5, 2, 5]);
+/// ```
+pub fn encode_wpp(txt: &str) -> Result<Vec<usize>, EncodingError> {
+    check_ascii(txt, false)?;
+
+    let mut encoded = Vec::new();
+    let mut words = 0;
+    for line in txt.lines() {
+        match words_of(line).count() {
+            0 if words > 0 => encoded.push(core::mem::take(&mut words)),
+            count => words += count,
+        }
+    }
+    if words > 0 {
+        encoded.push(words);
+    }
+
+    if encoded.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded text: {encoded:?}");
+    Ok(encoded)
+}
+
+/// Encodes a given text into a vector of word counts per sentence, followed by a checksum.
+///
+/// This returns the word counts of [`encode`] with one extra count appended, equal to the sum of the word counts
+/// modulo the number of characters in the character set, plus one. The checksum is therefore itself a valid code,
+/// and a cover text carrying it simply ends with one extra sentence of that many words. Use
+/// [`decode_verify_checksum`] to check and strip it when decoding, which detects most alterations of the cover
+/// text in transit.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence followed by the checksum.
+/// * `Err(CompareError)` - An error in case of invalid input text or an empty character set.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded or contains no valid sentences, an `EncodingError` is returned.
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_with_checksum;
+///
+/// let encoded = encode_with_checksum("Hello world. How are you?", "ABCD").expect("Failed to encode");
+/// // (2 + 3) % 4 + 1 = 2
+/// assert_eq!(encoded, vec![2, 3, 2]);
+/// ```
+pub fn encode_with_checksum(txt: &str, character_set: &str) -> Result<Vec<usize>, CompareError> {
+    let mut encoded = encode(txt)?;
+    let checksum = checksum_code(&encoded, character_set)?;
+    encoded.push(checksum);
+    Ok(encoded)
+}
+
+/// Lazily yields the word count of each sentence of a given text.
+///
+/// This is the iterator counterpart of [`encode`]: it splits on the [`DEFAULT_TERMINATORS`] and yields the word
+/// count of every non-empty sentence as it is reached, without collecting into a `Vec`. This makes it easy to
+/// compose with adapters such as `take` or `filter` before materializing anything.
+///
+/// The iterator does not perform the ASCII check that [`encode`] does, and yields nothing rather than an error
+/// when the text contains no valid sentences. Use [`encode_iter_checked`] if the cover text must be ASCII.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// An iterator over the word counts of the non-empty sentences of `txt`.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_iter;
+///
+/// let text = "Hello world. This is a test sentence! One more?";
+/// let first_two: Vec<usize> = encode_iter(text).take(2).collect();
+/// assert_eq!(first_two, vec![2, 5]);
+/// ```
+pub fn encode_iter(txt: &str) -> impl Iterator<Item = usize> + '_ {
+    Sentences::new(txt).map(|sentence| words_of(sentence).count())
+}
+
+/// Lazily yields the word count of each sentence of a given text, after checking that the text is ASCII.
+///
+/// This validates the text up front like [`encode`], then returns the same iterator as [`encode_iter`]. Like
+/// [`encode`], it skips sentences without words; since the check for valid sentences would require reading
+/// the whole text, a text without any yields an empty iterator rather than an error.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(impl Iterator<Item = usize>)` - An iterator over the word counts of the non-empty sentences of `txt`.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input.
+///
+/// # Errors
+/// If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode_iter_checked, EncodingError};
+///
+/// let counts: Vec<usize> = encode_iter_checked("Hello world. Hi!").expect("Failed to encode").collect();
+/// assert_eq!(counts, vec![2, 1]);
+/// assert!(matches!(encode_iter_checked("Héllo."), Err(EncodingError::NonAsciiInput)));
+/// ```
+pub fn encode_iter_checked(txt: &str) -> Result<impl Iterator<Item = usize> + '_, EncodingError> {
+    check_ascii(txt, false)?;
+    Ok(encode_iter(txt))
+}
+
+/// Encodes text read from a [`Read`] source into a vector of word counts per sentence.
+///
+/// This produces the same result as calling [`encode`] on the whole input, but reads it in fixed-size chunks
+/// so large cover texts never need to be loaded into memory at once. Words and sentences spanning two chunks
+/// are counted correctly, since the word and sentence state is carried across chunk boundaries.
+///
+/// # Arguments
+/// * `reader` - A [`Read`] source providing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of a read failure, non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If reading from `reader` fails, an `EncodingError::Io` error is returned with the error kind.
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_reader;
+///
+/// let text = "Hello world. This is a test sentence!";
+/// let encoded = encode_reader(text.as_bytes()).expect("Failed to encode");
+/// assert_eq!(encoded, vec![2, 5]);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_reader<R: Read>(mut reader: R) -> Result<Vec<usize>, EncodingError> {
+    let mut buffer = [0u8; 8192];
+    let mut encoded = Vec::new();
+    let mut words = 0;
+    let mut in_word = false;
+    let mut blank = true;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                warn!("Failed to read input: {e}");
+                return Err(EncodingError::Io(e.kind()));
+            }
+        };
+
+        for &byte in &buffer[..read] {
+            if !byte.is_ascii() {
+                warn!("Non-ASCII string encountered");
+                return Err(EncodingError::NonAsciiInput);
+            }
+            let c = char::from(byte);
+            blank &= c.is_whitespace();
+            if DEFAULT_TERMINATORS.contains(&c) {
+                words += usize::from(in_word);
+                if words > 0 {
+                    encoded.push(words);
+                }
+                words = 0;
+                in_word = false;
+            } else if c.is_whitespace() {
+                words += usize::from(in_word);
+                in_word = false;
+            } else {
+                in_word |= c.is_alphanumeric();
+            }
+        }
+    }
+
+    words += usize::from(in_word);
+    if words > 0 {
+        encoded.push(words);
+    }
+
+    if encoded.is_empty() {
+        return Err(no_sentences_error(blank));
+    }
+
+    debug!("Encoded text: {encoded:?}");
+    Ok(encoded)
+}
+
+/// Normalizes the whitespace of a text.
+///
+/// Every run of whitespace, including tabs and `\r\n` or `\n` line endings, is collapsed into a single
+/// space, and leading and trailing whitespace is removed. Text from different sources therefore compares
+/// equal once normalized, and it encodes to the same word counts as before normalization. Because line
+/// breaks become spaces, do not normalize text meant for [`encode_line_oriented`].
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be normalized.
+///
+/// # Returns
+/// * `String` - The text with its whitespace normalized.
+///
+/// # Examples
+/// ```
+/// use stego_wps::normalize;
+///
+/// assert_eq!(normalize("  Hello,\tworld.\r\nHow are\r\n you?  "), "Hello, world. How are you?");
+/// ```
+#[must_use]
+pub fn normalize(txt: &str) -> String {
+    txt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Details about a single sentence counted by [`encode_detailed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceInfo {
+    /// The number of words in the sentence, as returned by [`encode`].
+    pub word_count: usize,
+    /// The sentence text, without its terminator and with its whitespace normalized by [`normalize`].
+    pub text: String,
+    /// The words of the sentence, in order.
+    pub words: Vec<String>,
+}
+
+/// Encodes a given text into detailed information about each sentence.
+///
+/// This is meant for debugging cover texts: besides the word count that [`encode`] returns for each sentence,
+/// every [`SentenceInfo`] holds the sentence text and its individual words, which makes it easy to see which
+/// sentence produced which count when a decode comes out wrong. The word counts are exactly those of [`encode`].
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<SentenceInfo>)` - A vector with the details of each valid sentence.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_detailed;
+///
+/// let details = encode_detailed("Hello world. How are you?").expect("Failed to encode");
+/// assert_eq!(details[1].word_count, 3);
+/// assert_eq!(details[1].text, "How are you");
+/// assert_eq!(details[1].words, vec!["How", "are", "you"]);
+/// ```
+pub fn encode_detailed(txt: &str) -> Result<Vec<SentenceInfo>, EncodingError> {
+    let options = EncodeOptions::default();
+    check_ascii(txt, options.allow_non_ascii)?;
+
+    let details: Vec<SentenceInfo> = sentence_ranges(txt, &options)
+        .map(|(range, _)| {
+            let sentence = &txt[range];
+            let words: Vec<String> = words_of(sentence).map(str::to_string).collect();
+            SentenceInfo {
+                word_count: words.len(),
+                text: normalize(sentence),
+                words,
+            }
+        })
+        .filter(|info| info.word_count > 0)
+        .collect();
+
+    if details.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded details: {details:?}");
+    Ok(details)
+}
+
+/// Encodes a given text into word counts per sentence, paired with the terminator of each sentence.
+///
+/// This is the building block for rewriting a cover text while keeping its punctuation intact: it returns
+/// the same word counts as [`encode`], and alongside each count the character (`.`, `!` or `?`) that
+/// ended that sentence in the original text. A trailing sentence that is not followed by any terminator
+/// is paired with `.`, the terminator [`apply_changes`] uses for the sentences it adds.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<(usize, char)>)` - A vector of `(word count, terminator)` pairs, one per valid sentence.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_spans;
+///
+/// let spans = encode_spans("Is this it? Yes it is!").expect("Failed to encode");
+/// assert_eq!(spans, vec![(3, '?'), (3, '!')]);
+/// ```
+pub fn encode_spans(txt: &str) -> Result<Vec<(usize, char)>, EncodingError> {
+    let options = EncodeOptions::default();
+    check_ascii(txt, options.allow_non_ascii)?;
+
+    let spans: Vec<(usize, char)> = sentence_ranges(txt, &options)
+        .map(|(range, terminator)| {
+            (
+                count_words(&txt[range], &options),
+                terminator.unwrap_or('.'),
+            )
+        })
+        .filter(|&(count, _)| count > 0)
+        .collect();
+
+    if spans.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded spans: {spans:?}");
+    Ok(spans)
+}
+
+/// Encodes a given text into word counts per sentence, paired with the byte range of each sentence.
+///
+/// This returns the same word counts as [`encode`], and alongside each count the range of bytes the
+/// sentence occupies in `txt`. The range excludes the terminator that ended the sentence as well as any
+/// leading or trailing whitespace, so `&txt[range]` is exactly the sentence text, which makes it suitable
+/// for highlighting the counted sentences in the original string.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<(Range<usize>, usize)>)` - A vector of `(byte range, word count)` pairs, one per valid sentence.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_with_ranges;
+///
+/// let text = "Hello world. How are you?";
+/// let ranges = encode_with_ranges(text).expect("Failed to encode");
+/// assert_eq!(ranges, vec![(0..11, 2), (13..24, 3)]);
+/// assert_eq!(&text[ranges[1].0.clone()], "How are you");
+/// ```
+pub fn encode_with_ranges(txt: &str) -> Result<Vec<(Range<usize>, usize)>, EncodingError> {
+    let options = EncodeOptions::default();
+    check_ascii(txt, options.allow_non_ascii)?;
+
+    let ranges: Vec<(Range<usize>, usize)> = sentence_ranges(txt, &options)
+        .map(|(range, _)| {
+            let body = &txt[range.clone()];
+            let start = range.start + (body.len() - body.trim_start().len());
+            let end = range.start + body.trim_end().len();
+            (start..end.max(start), count_words(body, &options))
+        })
+        .filter(|&(_, count)| count > 0)
+        .collect();
+
+    if ranges.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded ranges: {ranges:?}");
+    Ok(ranges)
+}
+
+/// Encodes a given text into word counts per sentence, paired with the text of each sentence.
+///
+/// This returns the same word counts as [`encode`], and alongside each count a slice of `txt` holding the
+/// sentence that produced it, without its terminator and surrounding whitespace. The slices borrow from the
+/// input, so nothing is copied; see [`encode_detailed`] for an owned variant that also lists the words.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<(usize, &str)>)` - A vector of `(word count, sentence)` pairs, one per valid sentence.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the input text is not ASCII-encoded, an `EncodingError::NonAsciiInput` error is returned.
+/// - If the input text is empty or only whitespace, an `EncodingError::EmptyInput` error is returned.
+/// - If the input text does not contain any valid sentences, an `EncodingError::NoValidSentences`
+///   error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::encode_with_spans;
+///
+/// let spans = encode_with_spans("Hello world. How are you?").expect("Failed to encode");
+/// assert_eq!(spans, vec![(2, "Hello world"), (3, "How are you")]);
+/// ```
+pub fn encode_with_spans(txt: &str) -> Result<Vec<(usize, &str)>, EncodingError> {
+    check_ascii(txt, false)?;
+
+    let spans: Vec<(usize, &str)> = split_sentences(txt)
+        .into_iter()
+        .map(|sentence| (words_of(sentence).count(), sentence))
+        .collect();
+
+    if spans.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded spans: {spans:?}");
+    Ok(spans)
+}
+
+/// Splits a text into its sentences, using the same rules as [`encode`].
+///
+/// Each returned slice borrows from `txt` and is a sentence that [`encode`] counts, without its terminator
+/// and surrounding whitespace, so there is exactly one slice per word count [`encode`] returns. Sentences
+/// without any words are skipped. Unlike [`encode`], this does not check that the text is ASCII.
+///
+/// Since the terminators are dropped, the text cannot be rebuilt from these slices. To restore each sentence's
+/// original `.`, `!` or `?` after editing it, use [`split_sentences_with_terminators`] instead.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be split.
+///
+/// # Returns
+/// * `Vec<&str>` - The sentences of the text, in order.
+///
+/// # Examples
+/// ```
+/// use stego_wps::split_sentences;
+///
+/// let sentences = split_sentences("Hello world. .. How are you?  Fine");
+/// assert_eq!(sentences, vec!["Hello world", "How are you", "Fine"]);
+/// ```
+#[must_use]
+pub fn split_sentences(txt: &str) -> Vec<&str> {
+    Sentences::new(txt).collect()
+}
+
+/// An iterator over the sentences of a text, using the same rules as [`encode`].
+///
+/// This is the lazy counterpart of [`split_sentences`]: each item borrows from the text and is a sentence that
+/// [`encode`] counts, without its terminator and surrounding whitespace, and sentences without any words are
+/// skipped. It splits the text with the same code as [`encode`], using the default [`EncodeOptions`], so the
+/// two always agree. Nothing is allocated while iterating, so it suits walking large texts or stopping early.
+/// Like [`split_sentences`], it does not check that the text is ASCII.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode, Sentences};
+///
+/// let txt = "Hello world. .. How are you?  Fine";
+/// let mut sentences = Sentences::new(txt);
+/// assert_eq!(sentences.next(), Some("Hello world"));
+/// assert_eq!(sentences.next(), Some("How are you"));
+/// assert_eq!(Sentences::new(txt).count(), encode(txt).expect("Failed to encode").len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sentences<'a> {
+    ranges: SentenceRanges<'a>,
+}
+
+impl<'a> Sentences<'a> {
+    /// Creates an iterator over the sentences of `txt`, split like [`encode`] does.
+    #[must_use]
+    pub fn new(txt: &'a str) -> Self {
+        Self {
+            ranges: SentenceRanges {
+                boundaries: split_at_boundaries(txt, Cow::Owned(EncodeOptions::default())),
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let txt = self.ranges.boundaries.txt;
+        loop {
+            let (range, terminator) = self.ranges.next()?;
+            if let Some(count) =
+                sentence_word_count(txt, range.clone(), terminator, self.ranges.options())
+            {
+                if count > 0 {
+                    return Some(txt[range].trim());
+                }
+            }
+        }
+    }
+}
+
+/// Splits a text into its sentences, keeping the terminator that ended each one.
+///
+/// Each element is the raw body of a sentence, borrowed from `txt`, paired with the terminator that followed it,
+/// or `None` for a trailing fragment without a terminator. Unlike [`split_sentences`], bodies are neither
+/// trimmed nor filtered, so that concatenating every body with its terminator rebuilds `txt` exactly. This is
+/// the basis for rewriting a text one sentence at a time: [`apply_changes`] and [`generate_stego_text`] are built
+/// on it, and restore each sentence's `.`, `!` or `?` after changing its word count. Sentences are split on
+/// the [`DEFAULT_TERMINATORS`], and a text ending with a terminator has no empty trailing fragment.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be split.
+///
+/// # Returns
+/// * `Vec<(&str, Option<char>)>` - The sentence bodies and their terminators, in order.
+///
+/// # Examples
+/// ```
+/// use stego_wps::split_sentences_with_terminators;
+///
+/// let sentences = split_sentences_with_terminators("Hi there! How are you? Fine");
+/// assert_eq!(
+///     sentences,
+///     vec![("Hi there", Some('!')), (" How are you", Some('?')), (" Fine", None)]
+/// );
+/// ```
+#[must_use]
+pub fn split_sentences_with_terminators(txt: &str) -> Vec<(&str, Option<char>)> {
+    split_terminated(txt, DEFAULT_TERMINATORS)
+        .map(|(range, terminator)| (&txt[range], terminator))
+        .collect()
+}
+
+/// Decodes a vector of word counts per sentence into a string using a specified character set.
+///
+/// This function is part of a text-based steganography system using the Words Per Sentence (WPS) method.
+/// It decodes a sequence of word counts (represented as a vector of `usize`) back into a string based on a given
+/// character set. Each word count is used to find a corresponding character in the character set.
+/// The function ensures that the character set is not empty and that each word count is valid within the context of the character set.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of:
+///   - Empty character set (`DecodingError::EmptyCharacterSet`).
+///   - Invalid word count that does not correspond to any character in the set (`DecodingError::InvalidCode`).
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, an `DecodingError::EmptyCharacterSet` error is returned.
+/// - If a word count in the `encoded` vector does not correspond to a character in the character set (i.e., out of range),
+///   a `DecodingError::InvalidCode` error is returned with the invalid count.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode;
+/// use stego_wps::DecodingError;
+///
+/// let encoded = vec![3, 5, 1];
+/// let character_set = "ABCDE";
+/// match decode(&encoded, character_set) {
+///     Ok(decoded) => println!("Decoded string: {}", decoded),
+///     Err(e) => match e {
+///         DecodingError::EmptyCharacterSet => println!("Character set cannot be empty"),
+///         DecodingError::InvalidCode(code) => println!("Invalid code: {}", code),
+///         e => println!("Decoding failed: {}", e),
+///     },
+/// }
+/// ```
+pub fn decode(encoded: &[usize], character_set: &str) -> Result<String, DecodingError> {
+    decode_with_zero_policy(encoded, character_set, ZeroPolicy::Skip)
+}
+
+/// How decoding treats a code of `0`, which no character of the character set maps to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ZeroPolicy {
+    /// Skip zero codes silently. This is what [`decode`] does.
+    #[default]
+    Skip,
+    /// Reject zero codes with `DecodingError::InvalidCode(0)`.
+    Error,
+}
+
+/// Decodes a vector of word counts per sentence into a string, treating zero codes according to a [`ZeroPolicy`].
+///
+/// With [`ZeroPolicy::Skip`] this behaves exactly like [`decode`]. With [`ZeroPolicy::Error`], a code of `0` is
+/// reported as an invalid code instead of being skipped, for protocols where a zero count is an error condition
+/// that must be detected.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `zero_policy` - How to treat codes equal to `0`.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or an invalid code.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If `zero_policy` is [`ZeroPolicy::Error`] and a code is `0`, a `DecodingError::InvalidCode(0)` error is returned.
+/// - If a word count does not correspond to a character in the character set,
+///   a `DecodingError::InvalidCode` error is returned with the invalid count.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_with_zero_policy, DecodingError, ZeroPolicy};
+///
+/// let encoded = vec![1, 0, 2];
+/// assert_eq!(decode_with_zero_policy(&encoded, "AB", ZeroPolicy::Skip), Ok("AB".to_string()));
+/// assert_eq!(
+///     decode_with_zero_policy(&encoded, "AB", ZeroPolicy::Error),
+///     Err(DecodingError::InvalidCode(0))
+/// );
+/// ```
+pub fn decode_with_zero_policy(
+    encoded: &[usize],
+    character_set: &str,
+    zero_policy: ZeroPolicy,
+) -> Result<String, DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let decoded: Result<String, _> = encoded
+        .iter()
+        .filter(|&&code| code != 0 || zero_policy == ZeroPolicy::Error)
+        .map(|&code| char_for_code(&charset, code, 1))
+        .collect();
+
+    match decoded {
+        Ok(d) => {
+            debug!("Decoded string: {d}");
+            Ok(d)
+        }
+        Err(e) => {
+            warn!("Decoding error: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Decodes a vector of word counts per sentence into a string, stopping at a sentinel character.
+///
+/// Codes are decoded like in [`decode`] until one of them decodes to `sentinel`. The sentinel and every code
+/// after it are discarded, so a cover text can carry trailing sentences beyond the end of a fixed-length secret
+/// message. Since [`decode`] wraps codes larger than the character set around to its start, every code that
+/// wraps onto the sentinel's position stops decoding as well: with the sentinel at position `p` of a character
+/// set of `n` characters, codes `p`, `p + n`, `p + 2n` and so on all act as the sentinel. If the sentinel is not
+/// part of the character set, this behaves exactly like [`decode`].
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `sentinel` - The character marking the end of the message.
+///
+/// # Returns
+/// * `Ok(String)` - The `String` decoded from the word counts before the sentinel.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set.
+///
+/// # Errors
+/// This function returns a `DecodingError::EmptyCharacterSet` error if the character set provided is empty.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_until;
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ#";
+/// let decoded = decode_until(&[8, 9, 27, 1, 2], character_set, '#').expect("Failed to decode");
+/// assert_eq!(decoded, "HI");
+/// ```
+pub fn decode_until(
+    encoded: &[usize],
+    character_set: &str,
+    sentinel: char,
+) -> Result<String, DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let decoded: String = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| charset[(code - 1) % charset.len()])
+        .take_while(|&c| c != sentinel)
+        .collect();
+
+    debug!("Decoded string: {decoded}");
+    Ok(decoded)
+}
+
+/// Decodes a vector of word counts into a string, looking characters up through a secret permutation.
+///
+/// A fixed mapping such as `A = 1`, `B = 2` is easy to recover from a histogram of word counts. This function
+/// uses `permutation` as a simple substitution key: a word count of `n` decodes to the character at index
+/// `permutation[n - 1]` of the character set, instead of index `n - 1`. Codes are wrapped around the character set
+/// and zero codes are skipped, as in [`decode`]. See [`compare_with_permutation`] for the matching encoder.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `permutation` - The character set index for each code, containing every index of the character set once.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or an invalid permutation.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If `permutation` does not contain every index of the character set exactly once, a
+///   `DecodingError::InvalidPermutation` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_with_permutation;
+///
+/// let permutation = [2, 0, 3, 1];
+/// assert_eq!(decode_with_permutation(&[1, 2, 3, 4], "ABCD", &permutation).expect("Failed to decode"), "CADB");
+/// ```
+pub fn decode_with_permutation(
+    encoded: &[usize],
+    character_set: &str,
+    permutation: &[usize],
+) -> Result<String, DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    validate_permutation(permutation, charset.len())?;
+    let decoded: String = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| charset[permutation[(code - 1) % charset.len()]])
+        .collect();
+
+    debug!("Decoded string: {decoded}");
+    Ok(decoded)
+}
+
+/// Checks that `permutation` contains every index below `len` exactly once.
+fn validate_permutation(permutation: &[usize], len: usize) -> Result<(), DecodingError> {
+    let mut seen = vec![false; len];
+    if permutation.len() != len
+        || !permutation
+            .iter()
+            .all(|&index| index < len && !core::mem::replace(&mut seen[index], true))
+    {
+        warn!("Invalid permutation: {permutation:?}");
+        return Err(DecodingError::InvalidPermutation);
+    }
+    Ok(())
+}
+
+/// Decodes a vector of word counts into a string, undoing the salted shifts of [`compare_salted`].
+///
+/// With a fixed mapping, the same secret message always needs the same word counts, so two stego texts hiding
+/// `"YES"` share a recognizable pattern. The salted scheme shifts the code of every character by a keystream
+/// derived from `salt`, so the pattern changes with the salt. The keystream is a splitmix64 generator seeded with
+/// `salt`, yielding one shift per character modulo the length of the character set. This is obfuscation, not
+/// encryption: anyone who learns the salt, or tries enough of them, can read the message. Zero codes are skipped
+/// and don't consume a shift, and larger codes wrap around the character set, as in [`decode`].
+///
+/// Decoding with a different salt than the one used for encoding does not fail, but garbles the message.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `salt` - The salt that was passed to [`compare_salted`].
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set.
+///
+/// # Errors
+/// If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{apply_changes, compare_salted, decode_salted, encode};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "A short cover text. It has three sentences. This is the last one.";
+/// let changes = compare_salted("YES", cover_text, character_set, 42).expect("Failed to compare");
+/// let stego_text = apply_changes(cover_text, &changes, "word").expect("Failed to apply");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode_salted(&encoded, character_set, 42).expect("Failed to decode"), "YES");
+/// ```
+pub fn decode_salted(
+    encoded: &[usize],
+    character_set: &str,
+    salt: u64,
+) -> Result<String, DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let len = charset.len();
+    let decoded: String = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .zip(salt_shifts(salt, len))
+        .map(|(&code, shift)| charset[((code - 1) % len + len - shift) % len])
+        .collect();
+
+    debug!("Decoded string: {decoded}");
+    Ok(decoded)
+}
+
+/// Yields the keystream of [`compare_salted`] and [`decode_salted`]: one shift below `len` per character, drawn
+/// from a splitmix64 generator seeded with `salt`.
+fn salt_shifts(salt: u64, len: usize) -> impl Iterator<Item = usize> {
+    let modulus = len as u64;
+    let mut state = salt;
+    core::iter::from_fn(move || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        usize::try_from(z % modulus).ok()
+    })
+}
+
+/// Lazily decodes word counts into characters using a specified character set.
+///
+/// This is the iterator counterpart of [`decode`], and pairs with [`encode_iter`]: counts are consumed one at a
+/// time as the iterator is advanced, so they can be piped straight from a reader without buffering them all.
+/// Zero codes are skipped and larger codes wrap around the character set, exactly like in [`decode`], so collecting
+/// the iterator into a `Result<String, _>` gives the same result as [`decode`].
+///
+/// # Arguments
+/// * `counts` - The encoded word counts, as any iterable of `usize`.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// An iterator over the decoded characters. If the character set is empty, it yields a single
+/// `DecodingError::EmptyCharacterSet` error instead, without consuming any count.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode, decode_iter};
+///
+/// let counts = [8, 0, 9, 27];
+/// let decoded: Result<String, _> = decode_iter(counts, "ABCDEFGHIJKLMNOPQRSTUVWXYZ").collect();
+/// assert_eq!(decoded, decode(&counts, "ABCDEFGHIJKLMNOPQRSTUVWXYZ"));
+/// assert_eq!(decoded.expect("Failed to decode"), "HIA");
+/// ```
+pub fn decode_iter<I: IntoIterator<Item = usize>>(
+    counts: I,
+    character_set: &str,
+) -> impl Iterator<Item = Result<char, DecodingError>> {
+    let charset: Vec<char> = character_set.chars().collect();
+    let mut counts = counts.into_iter();
+    let mut failed = false;
+    core::iter::from_fn(move || {
+        if failed {
+            return None;
+        }
+        if charset.is_empty() {
+            warn!("Character set is empty");
+            failed = true;
+            return Some(Err(DecodingError::EmptyCharacterSet));
+        }
+        counts
+            .find(|&code| code != 0)
+            .map(|code| Ok(charset[(code - 1) % charset.len()]))
+    })
+}
+
+/// Decodes a vector of word counts into raw bytes.
+///
+/// In the binary payload mode, every byte value is a code of its own, as if the character set had 256 symbols:
+/// a sentence of `n` words carries the byte `n - 1`. This hides arbitrary data rather than text in a small
+/// alphabet, at the cost of sentences of up to 256 words, which is correct but impractical for natural-looking
+/// cover texts. Zero codes are skipped, as in [`decode`]. See [`encode_bytes`] and [`compare_bytes`] for the
+/// encoding side.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - The bytes decoded from the word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of a word count too large for a byte.
+///
+/// # Errors
+/// If a word count is larger than 256, a `DecodingError::InvalidCode` error is returned with the invalid count.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_bytes;
+///
+/// assert_eq!(decode_bytes(&[1, 256, 43]).expect("Failed to decode"), vec![0, 255, 42]);
+/// ```
+pub fn decode_bytes(encoded: &[usize]) -> Result<Vec<u8>, DecodingError> {
+    let decoded = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| u8::try_from(code - 1).map_err(|_| DecodingError::InvalidCode(code)))
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    debug!("Decoded bytes: {decoded:?}");
+    Ok(decoded)
+}
+
+/// Returns the word count of each sentence needed to carry raw bytes.
+///
+/// This is the encoding side of [`decode_bytes`]: the byte `b` needs a sentence of `b + 1` words, from 1 word
+/// for `0` up to 256 words for `255`. Like [`message_to_counts`], the result can be turned into a cover text
+/// with [`fill_template`], while [`compare_bytes`] computes the changes to an existing cover text.
+///
+/// # Arguments
+/// * `bytes` - The payload to be hidden.
+///
+/// # Returns
+/// * `Vec<usize>` - The word count of each sentence, one per byte.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_bytes, encode_bytes};
+///
+/// let counts = encode_bytes(&[0, 255, 42]);
+/// assert_eq!(counts, vec![1, 256, 43]);
+/// assert_eq!(decode_bytes(&counts).expect("Failed to decode"), vec![0, 255, 42]);
+/// ```
+#[must_use]
+pub fn encode_bytes(bytes: &[u8]) -> Vec<usize> {
+    bytes.iter().map(|&byte| usize::from(byte) + 1).collect()
+}
+
+/// Decodes a vector of word counts into several strings interleaved across the sentences.
+///
+/// Sentence `i` carries a character of stream `i % streams`, so with two streams the odd sentences (1st, 3rd, ...)
+/// carry the first message and the even ones the second. The word counts are dealt round-robin into `streams`
+/// lists, each of which is decoded like in [`decode`]. See [`compare_interleaved`] for the matching encoder.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `streams` - The number of interleaved messages.
+///
+/// # Returns
+/// * `Ok(Vec<String>)` - One decoded `String` per stream, in order.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or zero streams.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If `streams` is `0`, a `DecodingError::ZeroStreams` error is returned.
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_interleaved;
+///
+/// let decoded = decode_interleaved(&[8, 2, 9, 25], "ABCDEFGHIJKLMNOPQRSTUVWXYZ", 2).expect("Failed to decode");
+/// assert_eq!(decoded, vec!["HI", "BY"]);
+/// ```
+pub fn decode_interleaved(
+    encoded: &[usize],
+    character_set: &str,
+    streams: usize,
+) -> Result<Vec<String>, DecodingError> {
+    if streams == 0 {
+        warn!("Number of streams is zero");
+        return Err(DecodingError::ZeroStreams);
+    }
+
+    (0..streams)
+        .map(|stream| {
+            let counts: Vec<usize> = encoded
+                .iter()
+                .skip(stream)
+                .step_by(streams)
+                .copied()
+                .collect();
+            decode(&counts, character_set)
+        })
+        .collect()
+}
+
+/// Decodes a vector of word counts into a string, unpacking several characters from each sentence.
+///
+/// In the packed scheme, each sentence carries `chars_per_sentence` characters instead of one. The word count
+/// of a sentence minus one is read as a base-`N` number with `chars_per_sentence` digits, where `N` is the number
+/// of characters in the character set, and each digit (most significant first) selects a character. With a
+/// 26-letter alphabet and 2 characters per sentence, `"AA"` is a 1-word sentence and `"BA"` a 27-word sentence.
+///
+/// A sentence therefore needs up to `N.pow(chars_per_sentence)` words, which grows quickly: 676 words for two
+/// letters per sentence, and 17,576 for three. Zero codes are skipped, as in [`decode`]. See [`compare_packed`]
+/// for the matching encoder.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `chars_per_sentence` - The number of characters packed into each sentence.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of invalid arguments or an out-of-range code.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If `chars_per_sentence` is `0`, a `DecodingError::ZeroCharsPerSentence` error is returned.
+/// - If `N.pow(chars_per_sentence)` does not fit in a `usize`, a `DecodingError::CharsPerSentenceTooLarge` error
+///   is returned, since no word count needs that many digits.
+/// - If a word count is larger than `N.pow(chars_per_sentence)`, a `DecodingError::InvalidCode` error is
+///   returned with the invalid count.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_packed;
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// // "HI" is (7 * 26 + 8) + 1 = 191 words, "YA" is (24 * 26 + 0) + 1 = 625 words.
+/// assert_eq!(decode_packed(&[191, 625], character_set, 2).expect("Failed to decode"), "HIYA");
+/// ```
+#[doc(alias = "decode_base")]
+pub fn decode_packed(
+    encoded: &[usize],
+    character_set: &str,
+    chars_per_sentence: usize,
+) -> Result<String, DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let base = charset.len();
+    let max_code = packed_max_code(base, chars_per_sentence)?;
+
+    let mut decoded =
+        String::with_capacity(encoded.len().checked_mul(chars_per_sentence).unwrap_or(0));
+    let mut chunk = vec![charset[0]; chars_per_sentence];
+    for &code in encoded.iter().filter(|&&code| code != 0) {
+        if code > max_code {
+            warn!("Decoding error: {:?}", DecodingError::InvalidCode(code));
+            return Err(DecodingError::InvalidCode(code));
+        }
+        let mut value = code - 1;
+        for slot in chunk.iter_mut().rev() {
+            *slot = charset[value % base];
+            value /= base;
+        }
+        decoded.extend(&chunk);
+    }
+
+    debug!("Decoded string: {decoded}");
+    Ok(decoded)
+}
+
+/// Returns the largest word count a sentence packing `chars_per_sentence` characters of a `base`-character set can
+/// need, that is `base.pow(chars_per_sentence)`.
+fn packed_max_code(base: usize, chars_per_sentence: usize) -> Result<usize, DecodingError> {
+    if chars_per_sentence == 0 {
+        warn!("Chars per sentence is zero");
+        return Err(DecodingError::ZeroCharsPerSentence);
+    }
+    u32::try_from(chars_per_sentence)
+        .ok()
+        .and_then(|exp| base.checked_pow(exp))
+        .ok_or_else(|| {
+            warn!("Chars per sentence is too large: {chars_per_sentence}");
+            DecodingError::CharsPerSentenceTooLarge
+        })
+}
+
+/// Checks that a character set can be used for unambiguous encoding and decoding.
+///
+/// A character set with duplicate characters makes [`decode`] and [`compare`] produce ambiguous mappings, since
+/// several positions decode to the same character. This function catches such misconfigured character sets early.
+///
+/// # Arguments
+/// * `character_set` - A string slice (`&str`) representing the character set to validate.
+///
+/// # Returns
+/// * `Ok(())` - If the character set is non-empty and every character appears once.
+/// * `Err(DecodingError)` - A `DecodingError` describing the problem otherwise.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If the character set contains a character more than once, a `DecodingError::DuplicateCharacter`
+///   error is returned with the first repeated character.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{validate_charset, DecodingError};
+///
+/// assert_eq!(validate_charset("ABC"), Ok(()));
+/// assert_eq!(validate_charset("ABCA"), Err(DecodingError::DuplicateCharacter('A')));
+/// ```
+pub fn validate_charset(character_set: &str) -> Result<(), DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let mut seen = BTreeSet::new();
+    if let Some(c) = character_set.chars().find(|&c| !seen.insert(c)) {
+        warn!("Duplicate character in character set: {c:?}");
+        return Err(DecodingError::DuplicateCharacter(c));
+    }
+
+    Ok(())
+}
+
+/// A validated character set with precomputed lookups in both directions.
+///
+/// [`compare`] and [`decode`] rebuild their character set lookups on every call. When many messages are processed
+/// against the same character set, build a `CharsetIndex` once and pass it to [`compare_with_index`] and
+/// [`decode_with_index`] instead. Construction validates the character set with [`validate_charset`].
+///
+/// Codes are 1-based, as everywhere else in the crate: the first character of the set has code `1`.
+///
+/// Since an empty or duplicate character set cannot be constructed, taking a `&CharsetIndex` instead of a raw
+/// `&str` catches such misconfigurations where the character set is built rather than where it is used. Besides
+/// [`CharsetIndex::new`], it can be built with `TryFrom<&str>` or parsed with [`str::parse`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{CharsetIndex, DecodingError};
+///
+/// let index = CharsetIndex::new("ABC").expect("Invalid character set");
+/// assert_eq!(index.index_of('B'), Some(2));
+/// assert_eq!(index.char_at(2), Some('B'));
+/// assert_eq!(index.char_at(4), None);
+/// assert_eq!("ABC".parse(), Ok(index));
+/// assert_eq!(CharsetIndex::try_from("ABA"), Err(DecodingError::DuplicateCharacter('A')));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharsetIndex {
+    chars: Vec<char>,
+    codes: BTreeMap<char, usize>,
+}
+
+impl CharsetIndex {
+    /// Builds an index over `character_set`.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`validate_charset`]: `DecodingError::EmptyCharacterSet` for an empty
+    /// character set and `DecodingError::DuplicateCharacter` if a character appears more than once.
+    pub fn new(character_set: &str) -> Result<Self, DecodingError> {
+        validate_charset(character_set)?;
+        let chars: Vec<char> = character_set.chars().collect();
+        let codes = chars.iter().enumerate().map(|(i, &c)| (c, i + 1)).collect();
+        Ok(Self { chars, codes })
+    }
+
+    /// Returns the number of characters in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Returns `true` if the set has no characters, which a validated set never has.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Returns the 1-based code of `c`, or `None` if `c` is not in the set.
+    #[must_use]
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        self.codes.get(&c).copied()
+    }
+
+    /// Returns the character with the 1-based `code`, or `None` if `code` is `0` or larger than the set.
+    #[must_use]
+    pub fn char_at(&self, code: usize) -> Option<char> {
+        code.checked_sub(1).and_then(|i| self.chars.get(i)).copied()
+    }
+}
+
+impl TryFrom<&str> for CharsetIndex {
+    type Error = DecodingError;
+
+    fn try_from(character_set: &str) -> Result<Self, Self::Error> {
+        Self::new(character_set)
+    }
+}
+
+impl FromStr for CharsetIndex {
+    type Err = DecodingError;
+
+    fn from_str(character_set: &str) -> Result<Self, Self::Err> {
+        Self::new(character_set)
+    }
+}
+
+/// Decodes a vector of word counts per sentence into a string using a prebuilt [`CharsetIndex`].
+///
+/// This behaves exactly like [`decode`], including skipping zero codes and wrapping codes larger than the
+/// character set, but reuses the lookup table of `index` instead of building one on every call.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `index` - The [`CharsetIndex`] of the character set used for decoding.
+///
+/// # Returns
+/// A `String` decoded from the encoded word counts. Since `index` is validated on construction, decoding
+/// cannot fail.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_with_index, CharsetIndex};
+///
+/// let index = CharsetIndex::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ").expect("Invalid character set");
+/// assert_eq!(decode_with_index(&[8, 9], &index), "HI");
+/// assert_eq!(decode_with_index(&[3, 15], &index), "CO");
+/// ```
+#[must_use]
+pub fn decode_with_index(encoded: &[usize], index: &CharsetIndex) -> String {
+    let decoded: String = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| index.chars[(code - 1) % index.len()])
+        .collect();
+
+    debug!("Decoded string: {decoded}");
+    decoded
+}
+
+/// Decodes a vector of word counts per sentence into a string using a custom mapping from codes to characters.
+///
+/// For encodings that a flat character set cannot express, such as characters that depend on a lookup table
+/// or a shifted alphabet, the closure is called with every code, zeros included, and the characters it returns
+/// are collected in order.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `f` - A closure mapping a word count to its character, or `None` if the count is not valid.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` if the closure rejects a code.
+///
+/// # Errors
+/// This function returns a `DecodingError::InvalidCode` error with the first code for which the closure
+/// returns `None`.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_with, DecodingError};
+///
+/// // A ROT13-style table: 1 word decodes to 'N', 2 words to 'O', and so on.
+/// let rot13 = |code: usize| {
+///     let index = u8::try_from(code.checked_sub(1)?).ok().filter(|&i| i < 26)?;
+///     Some(char::from(b'A' + (index + 13) % 26))
+/// };
+/// assert_eq!(decode_with(&[21, 18, 25, 25, 2], rot13).expect("Failed to decode"), "HELLO");
+/// assert_eq!(decode_with(&[27], rot13), Err(DecodingError::InvalidCode(27)));
+/// ```
+pub fn decode_with<F: Fn(usize) -> Option<char>>(
+    encoded: &[usize],
+    f: F,
+) -> Result<String, DecodingError> {
+    let decoded = encoded
+        .iter()
+        .map(|&code| f(code).ok_or(DecodingError::InvalidCode(code)))
+        .collect::<Result<String, _>>()?;
+
+    debug!("Decoded string: {decoded}");
+    Ok(decoded)
+}
+
+/// Decodes a vector of word counts per sentence into a string, rejecting codes outside the character set.
+///
+/// Unlike [`decode`], which wraps codes larger than the character set back to its start (so with a 26-letter
+/// alphabet, a 27-word sentence decodes to the same character as a 1-word sentence), this function treats such
+/// codes as invalid. This makes it possible to detect corrupted or tampered cover texts whose word counts exceed
+/// the character set, instead of silently producing the wrong characters. Zero codes are skipped, as in [`decode`].
+///
+/// The character set is also checked with [`validate_charset`], since a character set with duplicates maps
+/// several codes to the same character.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an invalid character set or an out-of-range code.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If the character set contains a character more than once, a `DecodingError::DuplicateCharacter`
+///   error is returned with the first repeated character.
+/// - If a word count is larger than the number of characters in the character set,
+///   a `DecodingError::InvalidCode` error is returned with the invalid count.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode, decode_strict, DecodingError};
+///
+/// let character_set = "ABCDE";
+/// assert_eq!(decode(&[6], character_set).expect("Failed to decode"), "A");
+/// assert!(matches!(decode_strict(&[6], character_set), Err(DecodingError::InvalidCode(6))));
+/// ```
+pub fn decode_strict(encoded: &[usize], character_set: &str) -> Result<String, DecodingError> {
+    validate_charset(character_set)?;
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let decoded: Result<String, _> = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| {
+            charset
+                .get(code - 1)
+                .copied()
+                .ok_or(DecodingError::InvalidCode(code))
+        })
+        .collect();
+
+    match decoded {
+        Ok(d) => {
+            debug!("Decoded string: {d}");
+            Ok(d)
+        }
+        Err(e) => {
+            warn!("Decoding error: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Decodes a vector of word counts per sentence into a string using a set of multi-character tokens.
+///
+/// This generalizes [`decode`] from single characters to tokens such as the digraphs `"TH"` or `"CH"`, which
+/// carry more of the message per sentence: a word count of `n` selects `tokens[n - 1]`. Zero codes are skipped
+/// and codes larger than the number of tokens wrap around, as in [`decode`]. Tokens are not checked for
+/// duplicates, and since they are simply concatenated, tokens that are prefixes of each other, such as `"T"` and
+/// `"TH"`, make the decoded message ambiguous to encode again.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `tokens` - The tokens used for decoding, in code order.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty token set.
+///
+/// # Errors
+/// If `tokens` is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_tokens;
+///
+/// let tokens = ["A", "TH", "ER", " "];
+/// assert_eq!(decode_tokens(&[2, 3, 4, 1], &tokens).expect("Failed to decode"), "THER A");
+/// ```
+pub fn decode_tokens(encoded: &[usize], tokens: &[&str]) -> Result<String, DecodingError> {
+    if tokens.is_empty() {
+        warn!("Token set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let decoded: String = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| tokens[(code - 1) % tokens.len()])
+        .collect();
+
+    debug!("Decoded string: {decoded}");
+    Ok(decoded)
+}
+
+/// Decodes a vector of word counts per sentence into a string, replacing invalid codes with a placeholder.
+///
+/// Like [`String::from_utf8_lossy`], this never fails: every code that [`decode_strict`] would reject as out of
+/// range decodes to `placeholder` instead, so one corrupted sentence doesn't hide the rest of the message. Zero
+/// codes are skipped, as in [`decode`]. With an empty character set, every code decodes to `placeholder`. The
+/// character set is not checked for duplicates.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `placeholder` - The character emitted for each out-of-range code.
+///
+/// # Returns
+/// * `String` - The decoded message, with one character per non-zero code.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_lossy;
+///
+/// assert_eq!(decode_lossy(&[1, 9, 3], "ABCDE", '?'), "A?C");
+/// ```
+#[must_use]
+pub fn decode_lossy(encoded: &[usize], character_set: &str, placeholder: char) -> String {
+    let charset: Vec<char> = character_set.chars().collect();
+    let decoded: String = encoded
+        .iter()
+        .filter(|&&code| code != 0)
+        .map(|&code| charset.get(code - 1).copied().unwrap_or(placeholder))
+        .collect();
+
+    debug!("Decoded string: {decoded}");
+    decoded
+}
+
+/// Decodes a vector of word counts per sentence into a string, shifting the mapping by a given offset.
+///
+/// [`decode`] maps a word count of `n` to the character at index `n - 1`. This function generalizes that
+/// mapping to index `(code - offset) % charset_len`, so an `offset` of `0` makes a zero-word sentence a valid
+/// code for the first character, and larger offsets shift the mapping for obfuscation. Codes are wrapped around
+/// the character set like in [`decode`].
+///
+/// Unlike [`decode`], zero codes are not skipped: every code must be at least `offset`. A code smaller than
+/// `offset` would underflow, so it is reported as an invalid code instead.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `offset` - The code that maps to the first character of the character set.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or a code below `offset`.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If a code is smaller than `offset`, a `DecodingError::InvalidCode` error is returned with that code.
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_with_offset;
+///
+/// let character_set = "ABCDE";
+/// assert_eq!(decode_with_offset(&[0, 1, 2], character_set, 0).expect("Failed to decode"), "ABC");
+/// assert_eq!(decode_with_offset(&[3, 4, 5], character_set, 3).expect("Failed to decode"), "ABC");
+/// ```
+pub fn decode_with_offset(
+    encoded: &[usize],
+    character_set: &str,
+    offset: usize,
+) -> Result<String, DecodingError> {
+    if character_set.is_empty() {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let decoded: Result<String, _> = encoded
+        .iter()
+        .map(|&code| char_for_code(&charset, code, offset))
+        .collect();
+
+    match decoded {
+        Ok(d) => {
+            debug!("Decoded string: {d}");
+            Ok(d)
+        }
+        Err(e) => {
+            warn!("Decoding error: {e:?}");
+            Err(e)
+        }
+    }
+}
+
+/// Returns the character of the non-empty `charset` that `code` decodes to, where a code of `offset` maps to the
+/// first character and larger codes wrap around. This is the index function behind [`decode`], with an offset of
+/// `1`, and [`decode_with_offset`], so the two can't disagree.
+fn char_for_code(charset: &[char], code: usize, offset: usize) -> Result<char, DecodingError> {
+    code.checked_sub(offset)
+        .map(|index| charset[index % charset.len()])
+        .ok_or(DecodingError::InvalidCode(code))
+}
+
+/// Which word count maps to the first character of the character set.
+///
+/// The crate is 1-based by default: a sentence of `n` words stands for the `n`-th character, and since a
+/// sentence cannot have zero words, [`decode`] skips zero codes. In 0-based mode a sentence of `n` words stands
+/// for the character at index `n`, so a zero code is a valid symbol and is never skipped. Note that [`encode`]
+/// still drops sentences without words, so the 0-based mode is meant for integrations that supply their own
+/// word counts, including zeros.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum IndexBase {
+    /// A word count of `1` maps to the first character. This is what [`decode`] and [`compare`] use.
+    #[default]
+    One,
+    /// A word count of `0` maps to the first character.
+    Zero,
+}
+
+impl IndexBase {
+    /// Returns the word count that maps to the first character of the character set.
+    #[must_use]
+    pub const fn offset(self) -> usize {
+        match self {
+            Self::One => 1,
+            Self::Zero => 0,
+        }
+    }
+}
+
+/// Decodes a vector of word counts per sentence into a string, using the given [`IndexBase`].
+///
+/// With `IndexBase::One` this behaves exactly like [`decode`], skipping zero codes. With `IndexBase::Zero` a
+/// code of `n` decodes to the character at index `n`, wrapping around the character set, and zero codes decode
+/// to the first character like [`decode_with_offset`] with an offset of `0`.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `base` - The [`IndexBase`] of the codes.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set.
+///
+/// # Errors
+/// This function returns a `DecodingError::EmptyCharacterSet` error if the character set provided is empty.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_with_base, IndexBase};
+///
+/// let character_set = "ABCDE";
+/// assert_eq!(decode_with_base(&[1, 0, 2], character_set, IndexBase::One).expect("Failed to decode"), "AB");
+/// assert_eq!(decode_with_base(&[1, 0, 2], character_set, IndexBase::Zero).expect("Failed to decode"), "BAC");
+/// ```
+pub fn decode_with_base(
+    encoded: &[usize],
+    character_set: &str,
+    base: IndexBase,
+) -> Result<String, DecodingError> {
+    match base {
+        IndexBase::One => decode(encoded, character_set),
+        IndexBase::Zero => decode_with_offset(encoded, character_set, base.offset()),
+    }
+}
+
+/// Decodes a vector of word counts per sentence ending with a checksum, as produced by [`encode_with_checksum`].
+///
+/// The last code is checked against the sum of the other codes modulo the number of characters in the character
+/// set, plus one. If it matches, the other codes are decoded like in [`decode`] and the checksum is not part of
+/// the returned string.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts, ending with the checksum.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if the checksum matches.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or a checksum mismatch.
+///
+/// # Errors
+/// This function returns an error in the following cases:
+/// - If the character set provided is empty, a `DecodingError::EmptyCharacterSet` error is returned.
+/// - If `encoded` is empty or its last code is not the checksum of the other codes, a
+///   `DecodingError::ChecksumMismatch` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_verify_checksum, DecodingError};
+///
+/// assert_eq!(decode_verify_checksum(&[2, 3, 2], "ABCD").expect("Failed to decode"), "BC");
+/// assert_eq!(decode_verify_checksum(&[2, 4, 2], "ABCD"), Err(DecodingError::ChecksumMismatch));
+/// ```
+pub fn decode_verify_checksum(
+    encoded: &[usize],
+    character_set: &str,
+) -> Result<String, DecodingError> {
+    let Some((&checksum, counts)) = encoded.split_last() else {
+        warn!("No checksum to verify");
+        return Err(DecodingError::ChecksumMismatch);
+    };
+    if checksum_code(counts, character_set)? != checksum {
+        warn!("Checksum mismatch");
+        return Err(DecodingError::ChecksumMismatch);
+    }
+
+    decode(counts, character_set)
+}
+
+/// Computes the checksum code of `counts`: their sum modulo the length of the character set, plus one.
+fn checksum_code(counts: &[usize], character_set: &str) -> Result<usize, DecodingError> {
+    let len = character_set.chars().count();
+    if len == 0 {
+        warn!("Character set is empty");
+        return Err(DecodingError::EmptyCharacterSet);
+    }
+
+    let sum = counts
+        .iter()
+        .fold(0, |sum, &count| (sum + count % len) % len);
+    Ok(sum + 1)
+}
+
+/// Decodes a vector of word counts per sentence into a lowercase string, ignoring the case of the character set.
+///
+/// This is the decoding counterpart of [`compare_case_insensitive`]: the character set is lowercased before
+/// decoding, so a message hidden with a lowercase secret against an uppercase character set decodes back to
+/// the original lowercase text. Apart from that, it behaves exactly like [`decode`].
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+///
+/// # Returns
+/// * `Ok(String)` - The lowercase `String` decoded from the encoded word counts.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or an invalid code.
+///
+/// # Errors
+/// This function returns the same errors as [`decode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::decode_case_insensitive;
+///
+/// let decoded = decode_case_insensitive(&[8, 9], "ABCDEFGHIJKLMNOPQRSTUVWXYZ").expect("Failed to decode");
+/// assert_eq!(decoded, "hi");
+/// ```
+pub fn decode_case_insensitive(
+    encoded: &[usize],
+    character_set: &str,
+) -> Result<String, DecodingError> {
+    let folded: String = character_set.chars().map(fold_case).collect();
+    decode(encoded, &folded)
+}
+
+/// How [`decode_with_options`] changes the case of the decoded string.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Case {
+    /// Keep the case of the character set. This is what [`decode`] does.
+    #[default]
+    Preserve,
+    /// Uppercase the decoded string.
+    Upper,
+    /// Lowercase the decoded string, like [`decode_case_insensitive`].
+    Lower,
+}
+
+/// Options controlling how [`decode_with_options`] turns word counts into a string.
+///
+/// `DecodeOptions::default()` matches the behavior of [`decode`] exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    /// The case of the decoded string. Defaults to `Case::Preserve`.
+    pub case: Case,
+}
+
+/// Decodes a vector of word counts per sentence into a string, using the given [`DecodeOptions`].
+///
+/// The word counts are decoded like in [`decode`], then the options are applied to the decoded string. This
+/// lets a single character set decode to either uppercase or lowercase text, instead of maintaining one
+/// character set per case.
+///
+/// # Arguments
+/// * `encoded` - A slice of `usize` representing the encoded word counts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `options` - The [`DecodeOptions`] to decode with.
+///
+/// # Returns
+/// * `Ok(String)` - A `String` decoded from the encoded word counts if decoding is successful.
+/// * `Err(DecodingError)` - A `DecodingError` in case of an empty character set or an invalid code.
+///
+/// # Errors
+/// This function returns the same errors as [`decode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_with_options, Case, DecodeOptions};
+///
+/// let options = DecodeOptions { case: Case::Lower };
+/// let decoded = decode_with_options(&[8, 9], "ABCDEFGHIJKLMNOPQRSTUVWXYZ", &options).expect("Failed to decode");
+/// assert_eq!(decoded, "hi");
+/// ```
+pub fn decode_with_options(
+    encoded: &[usize],
+    character_set: &str,
+    options: &DecodeOptions,
+) -> Result<String, DecodingError> {
+    let decoded = decode(encoded, character_set)?;
+    Ok(match options.case {
+        Case::Preserve => decoded,
+        Case::Upper => decoded.to_uppercase(),
+        Case::Lower => decoded.to_lowercase(),
+    })
+}
+
+/// Zero-width characters that [`normalize_unicode_whitespace`] removes: zero-width space, non-joiner and joiner,
+/// word joiner and the byte order mark.
+const ZERO_WIDTH_CHARS: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+/// Replaces non-ASCII whitespace in `txt` with ASCII spaces and removes zero-width characters.
+fn normalize_unicode_whitespace(txt: &str) -> String {
+    txt.chars()
+        .filter(|c| !ZERO_WIDTH_CHARS.contains(c))
+        .map(|c| {
+            if c.is_whitespace() && !c.is_ascii() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Lowercases a single character, keeping it unchanged if it has no single-character lowercase form.
+fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+/// Compares a secret message with a cover text to calculate the necessary changes in word count per sentence to encode the message.
+///
+/// This function is a part of a text-based steganography system using the Words Per Sentence (WPS) method.
+/// It calculates the differences in word count needed for each sentence of the cover text to match the encoded form of the secret message.
+/// The function returns a vector where each element represents the change in the number of words required for a corresponding sentence
+/// in the cover text to encode a character of the secret message using the provided character set.
+///
+/// If a character appears more than once in the character set, its last occurrence is used. Every function that
+/// maps characters to codes follows this rule; use [`validate_charset`] to reject such character sets instead.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+///   Positive values indicate additional words needed, while negative values indicate words to be removed.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process, such as value out of range, encoding errors, or missing characters in the character set.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::compare;
+/// use stego_wps::CompareError;
+///
+/// let secret_message = "HELLO";
+/// let cover_text = "This is a sentence. Another one here.";
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// match compare(secret_message, cover_text, character_set) {
+///     Ok(changes) => println!("Changes needed: {:?}", changes),
+///     Err(e) => eprintln!("Comparison failed: {:?}", e),
+/// }
+/// ```
+pub fn compare(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<isize>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let charset_map = charset_positions(character_set)?;
+    compare_with_lookup(secret_message, cover_text, |c| charset_map.get(&c).copied())
+}
+
+/// A word count edit to a single sentence, as returned by [`compare_ops`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SentenceOp {
+    /// Add this many words to the sentence.
+    Add(usize),
+    /// Remove this many words from the sentence. Sentences beyond the end of the secret message have all of
+    /// their words removed.
+    Remove(usize),
+    /// Leave the sentence as it is.
+    Keep,
+    /// Append a new sentence with this many words, for characters beyond the cover text's sentence count.
+    NewSentence(usize),
+}
+
+/// Compares a secret message with a cover text like [`compare`], describing the changes as edit operations.
+///
+/// The operations are laid out like the changes of [`compare`], one per sentence: positive changes become
+/// `SentenceOp::Add`, negative ones `SentenceOp::Remove` and zero `SentenceOp::Keep`. Changes past the last
+/// sentence of the cover text become `SentenceOp::NewSentence`, carrying the word count of the new sentence.
+/// This makes it easy to render instructions such as "add 3 words to sentence 2".
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<SentenceOp>)` - The edit operation for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare_ops, SentenceOp};
+///
+/// let cover_text = "One two three. One two.";
+/// let ops = compare_ops("BBD", cover_text, "ABCD").expect("Failed to compare");
+/// assert_eq!(ops, vec![SentenceOp::Remove(1), SentenceOp::Keep, SentenceOp::NewSentence(4)]);
+/// ```
+pub fn compare_ops(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<SentenceOp>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let charset_map = charset_positions(character_set)?;
+    let cover_encoded = encode(cover_text)?;
+    let targets = secret_message
+        .chars()
+        .map(|c| {
+            charset_map
+                .get(&c)
+                .copied()
+                .ok_or(CompareError::CharacterNotFound(c))
+        })
+        .collect::<Result<Vec<isize>, _>>()?;
+    let changes = changes_for_targets(&targets, &cover_encoded)?;
+    Ok(changes
+        .iter()
+        .enumerate()
+        .map(|(i, &change)| {
+            let words = change.unsigned_abs();
+            if i >= cover_encoded.len() {
+                return SentenceOp::NewSentence(words);
+            }
+            match change.cmp(&0) {
+                Ordering::Greater => SentenceOp::Add(words),
+                Ordering::Less => SentenceOp::Remove(words),
+                Ordering::Equal => SentenceOp::Keep,
+            }
+        })
+        .collect())
+}
+
+/// Compares a secret message with a cover text, using the given [`IndexBase`].
+///
+/// With `IndexBase::One` this behaves exactly like [`compare`]. With `IndexBase::Zero` every target word count
+/// is one less, so the changes are meant to be decoded with [`decode_with_base`] and `IndexBase::Zero`. Since
+/// [`encode`] drops sentences without words, a target of zero words only round-trips through an integration
+/// that preserves empty sentences.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `base` - The [`IndexBase`] of the target word counts.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` with one change per sentence of the cover text.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_with_base, IndexBase};
+///
+/// let character_set = "ABCDE";
+/// let cover_text = "One two. Three four five.";
+/// let one_based = compare_with_base("CE", cover_text, character_set, IndexBase::One);
+/// assert_eq!(one_based, compare("CE", cover_text, character_set));
+/// let zero_based = compare_with_base("CE", cover_text, character_set, IndexBase::Zero);
+/// assert_eq!(zero_based, Ok(vec![0, 1]));
+/// ```
+pub fn compare_with_base(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    base: IndexBase,
+) -> Result<Vec<isize>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let shift = isize::try_from(1 - base.offset()).map_err(|_| CompareError::ValueOutOfRange)?;
+    let charset_map = charset_positions(character_set)?;
+    compare_with_lookup(secret_message, cover_text, |c| {
+        charset_map.get(&c).map(|pos| pos - shift)
+    })
+}
+
+/// Converts a secret message into the word counts per sentence that encode it.
+///
+/// Each character is mapped to its 1-based position in the character set, which is the number of words its
+/// sentence must have. This is the primitive for generating a cover text from scratch: [`compare`] is these
+/// counts minus the counts of [`encode`] on the cover text. If the character set contains a character more than
+/// once, its last position is used, as in [`compare`].
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - The target word count of each sentence, one per character of the secret message.
+/// * `Err(CompareError)` - An error if a character of the secret message is not in the character set.
+///
+/// # Errors
+/// This function returns an error if:
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode, message_to_counts};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let counts = message_to_counts("HELLO", character_set).expect("Failed to convert");
+/// assert_eq!(counts, vec![8, 5, 12, 12, 15]);
+/// assert_eq!(decode(&counts, character_set).expect("Failed to decode"), "HELLO");
+/// ```
+pub fn message_to_counts(
+    secret_message: &str,
+    character_set: &str,
+) -> Result<Vec<usize>, CompareError> {
+    message_to_counts_with_base(secret_message, character_set, IndexBase::One)
+}
+
+/// Converts a secret message into the word counts per sentence that encode it, using the given [`IndexBase`].
+///
+/// With `IndexBase::One` this behaves exactly like [`message_to_counts`]. With `IndexBase::Zero` every count is
+/// one less, so the first character of the character set becomes a sentence without words.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `base` - The [`IndexBase`] of the counts.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - The target word count of each sentence, one per character of the secret message.
+/// * `Err(CompareError)` - An error if a character of the secret message is not in the character set.
+///
+/// # Errors
+/// This function returns an error if:
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode_with_base, message_to_counts_with_base, IndexBase};
+///
+/// let character_set = "ABCDE";
+/// let counts = message_to_counts_with_base("BAD", character_set, IndexBase::Zero).expect("Failed to convert");
+/// assert_eq!(counts, vec![1, 0, 3]);
+/// assert_eq!(decode_with_base(&counts, character_set, IndexBase::Zero).expect("Failed to decode"), "BAD");
+/// ```
+pub fn message_to_counts_with_base(
+    secret_message: &str,
+    character_set: &str,
+    base: IndexBase,
+) -> Result<Vec<usize>, CompareError> {
+    let charset_map = charset_positions(character_set)?;
+    secret_message
+        .chars()
+        .map(|c| {
+            charset_map
+                .get(&c)
+                .map(|pos| pos.unsigned_abs() + base.offset() - 1)
+                .ok_or(CompareError::CharacterNotFound(c))
+        })
+        .collect()
+}
+
+/// Compares a secret message with a cover text using a prebuilt [`CharsetIndex`].
+///
+/// This behaves exactly like [`compare`], but reuses the lookup table of `index` instead of building one on every
+/// call, which amortizes the setup cost when many secret messages are compared against the same character set.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `index` - The [`CharsetIndex`] of the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_with_index, CharsetIndex};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let index = CharsetIndex::new(character_set).expect("Invalid character set");
+/// let cover_text = "This is a sentence. Another one here.";
+/// assert_eq!(
+///     compare_with_index("HELLO", cover_text, &index),
+///     compare("HELLO", cover_text, character_set),
+/// );
+/// ```
+pub fn compare_with_index(
+    secret_message: &str,
+    cover_text: &str,
+    index: &CharsetIndex,
+) -> Result<Vec<isize>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    compare_with_lookup(secret_message, cover_text, |c| {
+        index
+            .index_of(c)
+            .and_then(|code| isize::try_from(code).ok())
+    })
+}
+
+/// Compares a secret message with a cover text, mapping characters to word counts through a secret permutation.
+///
+/// This is the encoder for [`decode_with_permutation`]: the character at index `permutation[n - 1]` of the
+/// character set needs a sentence of `n` words. The changes are laid out like those of [`compare`].
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `permutation` - The character set index for each code, containing every index of the character set once.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - `permutation` does not contain every index of the character set exactly once
+///   (`DecodingError::InvalidPermutation`).
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::compare_with_permutation;
+///
+/// let permutation = [2, 0, 3, 1];
+/// let cover_text = "One two. One two.";
+/// assert_eq!(compare_with_permutation("CA", cover_text, "ABCD", &permutation), Ok(vec![-1, 0]));
+/// ```
+pub fn compare_with_permutation(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    permutation: &[usize],
+) -> Result<Vec<isize>, CompareError> {
+    let charset: Vec<char> = character_set.chars().collect();
+    validate_permutation(permutation, charset.len())?;
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let codes = permutation
+        .iter()
+        .enumerate()
+        .map(|(code, &index)| {
+            isize::try_from(code).map_or(Err(CompareError::ValueOutOfRange), |val| {
+                Ok((charset[index], val + 1))
+            })
+        })
+        .collect::<Result<BTreeMap<char, isize>, _>>()?;
+    compare_with_lookup(secret_message, cover_text, |c| codes.get(&c).copied())
+}
+
+/// Compares a secret message with a cover text, shifting each character's code by a keystream derived from a salt.
+///
+/// This is the encoder for [`decode_salted`], see there for how the keystream is derived. The code of the `i`-th
+/// character of the secret message is shifted by the `i`-th value of the keystream, wrapping around the character
+/// set, so the same secret message needs different word counts under different salts. The changes are laid out
+/// like those of [`compare`].
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `salt` - The salt seeding the keystream, which must be passed to [`decode_salted`] as well.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_salted};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "A short cover text. It has three sentences. This is the last one.";
+/// assert_ne!(
+///     compare_salted("YES", cover_text, character_set, 1),
+///     compare_salted("YES", cover_text, character_set, 2)
+/// );
+/// ```
+pub fn compare_salted(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    salt: u64,
+) -> Result<Vec<isize>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let charset: Vec<char> = character_set.chars().collect();
+    let positions = charset_indices(character_set);
+    let indices = secret_message
+        .chars()
+        .map(|c| {
+            positions
+                .get(&c)
+                .copied()
+                .ok_or(CompareError::CharacterNotFound(c))
+        })
+        .collect::<Result<Vec<usize>, _>>()?;
+    let targets = indices
+        .into_iter()
+        .zip(salt_shifts(salt, charset.len()))
+        .map(|(index, shift)| {
+            isize::try_from((index + shift) % charset.len() + 1)
+                .map_err(|_| CompareError::ValueOutOfRange)
+        })
+        .collect::<Result<Vec<isize>, _>>()?;
+
+    changes_for_targets(&targets, &encode(cover_text)?)
+}
+
+/// Compares a secret message with a cover text like [`compare`], adding a checksum sentence at the end.
+///
+/// This is the encoder for [`decode_verify_checksum`]. The target word counts are those of [`compare`], followed
+/// by one more: the sum of the secret message's codes modulo the number of characters in the character set, plus
+/// one, just like [`encode_with_checksum`]. A stego text built from these changes ends with an extra sentence of
+/// that many words, so rewording any sentence of it is detected when decoding, instead of silently producing
+/// wrong characters. An empty secret message still gets its checksum sentence.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The character set is empty (`DecodingError::EmptyCharacterSet`).
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{apply_changes, compare_with_checksum, decode_verify_checksum, encode};
+///
+/// let character_set = "ABCD";
+/// let cover_text = "One two. One two.";
+/// let changes = compare_with_checksum("CA", cover_text, character_set).expect("Failed to compare");
+/// // (3 + 1) % 4 + 1 = 1
+/// assert_eq!(changes, vec![1, -1, 1]);
+///
+/// let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply changes");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode_verify_checksum(&encoded, character_set).expect("Failed to decode"), "CA");
+/// ```
+pub fn compare_with_checksum(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<isize>, CompareError> {
+    let mut codes = message_to_counts(secret_message, character_set)?;
+    codes.push(checksum_code(&codes, character_set)?);
+    let targets = codes
+        .into_iter()
+        .map(|code| isize::try_from(code).map_err(|_| CompareError::ValueOutOfRange))
+        .collect::<Result<Vec<isize>, _>>()?;
+
+    changes_for_targets(&targets, &encode(cover_text)?)
+}
+
+/// Compares a secret message with a cover text, choosing which sentence carries which character to minimize edits.
+///
+/// [`compare`] assigns the `i`-th character of the secret message to the `i`-th sentence, which can require large
+/// edits even when the cover text has a sentence of exactly the right length elsewhere. This function instead
+/// solves the assignment problem: each character is assigned to a distinct sentence so that the total number of
+/// words added and removed is minimal. Sentences left without a character are kept unchanged, and if the secret
+/// message has more characters than the cover text has sentences, the remaining characters get new sentences
+/// appended at the end, like in [`compare`].
+///
+/// Since the characters are no longer in sentence order, decoding requires the returned assignment:
+/// `assignment[i]` is the index of the sentence carrying the `i`-th character, among the word counts that
+/// [`encode`] returns for the stego text. Pick the word counts in that order before calling [`decode`], as in the
+/// example below. The assignment therefore acts as a key that must be shared with the recipient.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok((Vec<isize>, Vec<usize>))` - The change in word count for each sentence, laid out like those of
+///   [`compare`], and the sentence index assigned to each character of the secret message.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{apply_changes, compare_optimal, decode, encode};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "One. One two three four five. One two.";
+/// let (changes, assignment) = compare_optimal("BAD", cover_text, character_set).expect("Failed to compare");
+/// assert_eq!(changes, vec![0, -1, 0]);
+/// assert_eq!(assignment, vec![2, 0, 1]);
+///
+/// let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply changes");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// let codes: Vec<usize> = assignment.iter().map(|&sentence| encoded[sentence]).collect();
+/// assert_eq!(decode(&codes, character_set).expect("Failed to decode"), "BAD");
+/// ```
+pub fn compare_optimal(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<(Vec<isize>, Vec<usize>), CompareError> {
+    if secret_message.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+
+    let cover_encoded = encode(cover_text)?;
+    let targets = message_to_counts(secret_message, character_set)?;
+
+    // With absolute differences as costs, some optimal assignment never crosses: once both sides are sorted,
+    // the i-th smallest assigned target goes to the i-th smallest assigned sentence. A target without a
+    // sentence costs its full length, and a sentence without a target costs nothing.
+    let mut target_order: Vec<usize> = (0..targets.len()).collect();
+    target_order.sort_by_key(|&i| targets[i]);
+    let mut sentence_order: Vec<usize> = (0..cover_encoded.len()).collect();
+    sentence_order.sort_by_key(|&j| cover_encoded[j]);
+
+    // Only two rows of costs are kept. The backtrack instead reads the choice made for every cell, packed as
+    // 2 bits per cell, so memory grows by n * m / 4 bytes rather than n * m words.
+    let (n, m) = (targets.len(), cover_encoded.len());
+    let cells = n.checked_mul(m + 1).ok_or(CompareError::ValueOutOfRange)?;
+    let mut choices = vec![0u8; cells.div_ceil(4)];
+    let cell = |i: usize, j: usize| (i - 1) * (m + 1) + j;
+    let mut previous = vec![0usize; m + 1];
+    let mut current = vec![0usize; m + 1];
+    for i in 1..=n {
+        let target = targets[target_order[i - 1]];
+        current[0] = previous[0].saturating_add(target);
+        set_choice(&mut choices, cell(i, 0), Choice::SkipTarget);
+        for j in 1..=m {
+            let count = cover_encoded[sentence_order[j - 1]];
+            let skip_sentence = current[j - 1];
+            let assign = previous[j - 1].saturating_add(target.abs_diff(count));
+            let best = skip_sentence
+                .min(previous[j].saturating_add(target))
+                .min(assign);
+            let choice = if best == skip_sentence {
+                Choice::SkipSentence
+            } else if best == assign {
+                Choice::Assign
+            } else {
+                Choice::SkipTarget
+            };
+            set_choice(&mut choices, cell(i, j), choice);
+            current[j] = best;
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+
+    let mut assignment = vec![None; n];
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        match get_choice(&choices, cell(i, j)) {
+            Choice::SkipSentence => j -= 1,
+            Choice::Assign => {
+                assignment[target_order[i - 1]] = Some(sentence_order[j - 1]);
+                i -= 1;
+                j -= 1;
+            }
+            Choice::SkipTarget => i -= 1,
+        }
+    }
+
+    let to_isize = |value: usize| isize::try_from(value).map_err(|_| CompareError::ValueOutOfRange);
+    let mut changes = vec![0; m];
+    let mut sentences = Vec::with_capacity(n);
+    for (&target, sentence) in targets.iter().zip(assignment) {
+        if let Some(j) = sentence {
+            changes[j] = to_isize(target)? - to_isize(cover_encoded[j])?;
+            sentences.push(j);
+        } else {
+            sentences.push(changes.len());
+            changes.push(to_isize(target)?);
+        }
+    }
+
+    debug!("Optimal changes: {changes:?}, assignment: {sentences:?}");
+    Ok((changes, sentences))
+}
+
+/// The step [`compare_optimal`] took to reach a cell of its cost table.
+#[derive(Clone, Copy)]
+enum Choice {
+    SkipSentence,
+    Assign,
+    SkipTarget,
+}
+
+/// Stores the choice for `cell` in a table packing 2 bits per cell.
+fn set_choice(choices: &mut [u8], cell: usize, choice: Choice) {
+    let shift = 2 * (cell % 4);
+    choices[cell / 4] = (choices[cell / 4] & !(0b11 << shift)) | ((choice as u8) << shift);
+}
+
+/// Reads the choice for `cell` back from a table written by [`set_choice`].
+const fn get_choice(choices: &[u8], cell: usize) -> Choice {
+    match (choices[cell / 4] >> (2 * (cell % 4))) & 0b11 {
+        0 => Choice::SkipSentence,
+        1 => Choice::Assign,
+        _ => Choice::SkipTarget,
+    }
+}
+
+/// Compares a binary payload with a cover text to calculate the necessary changes in word count per sentence.
+///
+/// This is the binary counterpart of [`compare`]: each byte `b` of `bytes` needs a sentence of `b + 1` words, as
+/// returned by [`encode_bytes`], and [`decode_bytes`] reads the payload back. The changes are laid out like those
+/// of [`compare`]. An empty payload yields no changes.
+///
+/// # Arguments
+/// * `bytes` - The payload to be hidden.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::compare_bytes;
+///
+/// let cover_text = "One two three. One.";
+/// assert_eq!(compare_bytes(&[2, 9], cover_text), Ok(vec![0, 9]));
+/// ```
+pub fn compare_bytes(bytes: &[u8], cover_text: &str) -> Result<Vec<isize>, CompareError> {
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let targets: Vec<isize> = bytes.iter().map(|&byte| isize::from(byte) + 1).collect();
+    changes_for_targets(&targets, &encode(cover_text)?)
+}
+
+/// Picks the cover text among several candidates that needs the fewest changes to hide a secret message.
+///
+/// Each candidate is compared with the secret message using [`compare`], and the cost of a candidate is the sum
+/// of the absolute changes, that is the number of words to add or remove. The candidate with the lowest cost is
+/// returned along with that cost; if several candidates tie, the first one wins.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `covers` - The candidate cover texts.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok((&str, isize))` - The cover text needing the fewest changes and the total number of words to change.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - `covers` is empty (`NoCoverTexts`).
+/// - The comparison of any candidate fails, with the error returned by [`compare`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::best_cover;
+///
+/// let covers = ["One two three four five. One.", "One two. One two three."];
+/// assert_eq!(best_cover("BC", &covers, "ABC"), Ok((covers[1], 0)));
+/// ```
+pub fn best_cover<'a>(
+    secret_message: &str,
+    covers: &[&'a str],
+    character_set: &str,
+) -> Result<(&'a str, isize), CompareError> {
+    let mut best: Option<(&'a str, isize)> = None;
+    for &cover in covers {
+        let cost = compare(secret_message, cover, character_set)?
+            .iter()
+            .map(|change| change.unsigned_abs())
+            .sum::<usize>();
+        let cost = isize::try_from(cost).map_err(|_| CompareError::ValueOutOfRange)?;
+        if best.is_none_or(|(_, lowest)| cost < lowest) {
+            best = Some((cover, cost));
+        }
+    }
+
+    best.ok_or(CompareError::NoCoverTexts)
+}
+
+/// Compares several secret messages with a cover text, interleaving them across the sentences.
+///
+/// This is the encoder for [`decode_interleaved`]: the secret messages are interleaved character by character,
+/// so sentence `i` carries a character of `secret_messages[i % secret_messages.len()]`, and the result is compared
+/// like in [`compare`]. All secret messages must have the same number of characters, so that every round of
+/// sentences carries one character of each.
+///
+/// # Arguments
+/// * `secret_messages` - The secret messages to be encoded, one per stream.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - `secret_messages` is empty (`DecodingError::ZeroStreams`).
+/// - The secret messages do not all have the same number of characters (`MismatchedSecretLengths`).
+/// - Any of the errors of [`compare`] occurs.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_interleaved};
+///
+/// let cover_text = "This is a sentence. Another one here.";
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// assert_eq!(
+///     compare_interleaved(&["HI", "BY"], cover_text, character_set),
+///     compare("HBIY", cover_text, character_set),
+/// );
+/// ```
+pub fn compare_interleaved(
+    secret_messages: &[&str],
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<isize>, CompareError> {
+    let Some(first) = secret_messages.first() else {
+        warn!("No secret messages to interleave");
+        return Err(DecodingError::ZeroStreams.into());
+    };
+    let len = first.chars().count();
+    if secret_messages
+        .iter()
+        .any(|secret| secret.chars().count() != len)
+    {
+        warn!("Interleaved secret messages have different lengths");
+        return Err(CompareError::MismatchedSecretLengths);
+    }
+
+    let mut streams: Vec<_> = secret_messages
+        .iter()
+        .map(|secret| secret.chars())
+        .collect();
+    let mut interleaved = String::new();
+    for _ in 0..len {
+        for stream in &mut streams {
+            interleaved.extend(stream.next());
+        }
+    }
+    compare(&interleaved, cover_text, character_set)
+}
+
+/// Compares a secret message with a cover text, failing if the cover text has too few sentences.
+///
+/// [`compare`] handles a secret message longer than the cover text by appending extra changes, which amounts to
+/// asking for new sentences to be added. For workflows where the cover text is fixed, this function instead fails
+/// with `CompareError::InsufficientCapacity` when the secret message has more characters than the cover text has
+/// sentences. Otherwise, it returns exactly what [`compare`] returns.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` with one change per sentence of the cover text.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`], and additionally:
+/// - If the secret message has more characters than the cover text has sentences,
+///   a `CompareError::InsufficientCapacity` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare_strict, CompareError};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// assert_eq!(
+///     compare_strict("HELLO", "One sentence. Two sentences.", character_set),
+///     Err(CompareError::InsufficientCapacity { have: 2, need: 5 })
+/// );
+/// ```
+pub fn compare_strict(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<isize>, CompareError> {
+    check_capacity(secret_message, cover_text)?;
+    compare(secret_message, cover_text, character_set)
+}
+
+/// Fails with `CompareError::InsufficientCapacity` if the secret message has more characters than the cover
+/// text has sentences.
+fn check_capacity(secret_message: &str, cover_text: &str) -> Result<(), CompareError> {
+    let have = capacity(cover_text)?;
+    let need = secret_message.chars().count();
+    if need > have {
+        warn!("Cover text has {have} sentences, but the secret message needs {need}");
+        return Err(CompareError::InsufficientCapacity { have, need });
+    }
+    Ok(())
+}
+
+/// Compares a secret message, padded to a fixed length, with a cover text.
+///
+/// The number of sentences a stego text changes reveals the length of the secret message. This function pads the
+/// secret message with `pad_char` up to `total_len` characters before comparing it like [`compare`], so every
+/// message of up to `total_len` characters needs the same number of sentences. If `pad_char` does not occur in
+/// the secret message itself, [`decode_until`] with `pad_char` as the sentinel recovers the unpadded message.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `total_len` - The number of characters to pad the secret message to.
+/// * `pad_char` - The character appended to the secret message, which must be in the character set.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`], and additionally:
+/// - If the secret message has more than `total_len` characters, a `CompareError::SecretTooLong` error is
+///   returned.
+/// - If `pad_char` is not in the character set, a `CompareError::CharacterNotFound` error is returned, even if
+///   no padding is needed.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{apply_changes, compare_padded, decode_until, encode};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ ";
+/// let cover_text = "One. Two. Three. Four. Five.";
+/// let changes = compare_padded("HI", cover_text, character_set, 5, ' ').expect("Failed to compare");
+/// assert_eq!(changes.len(), 5);
+/// let stego_text = apply_changes(cover_text, &changes, "word").expect("Failed to apply");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode_until(&encoded, character_set, ' ').expect("Failed to decode"), "HI");
+/// ```
+pub fn compare_padded(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    total_len: usize,
+    pad_char: char,
+) -> Result<Vec<isize>, CompareError> {
+    if !character_set.contains(pad_char) {
+        return Err(CompareError::CharacterNotFound(pad_char));
+    }
+
+    let length = secret_message.chars().count();
+    if length > total_len {
+        warn!("Secret message has {length} characters, more than the maximum of {total_len}");
+        return Err(CompareError::SecretTooLong {
+            length,
+            max: total_len,
+        });
+    }
+
+    let mut padded = String::from(secret_message);
+    padded.extend(core::iter::repeat_n(pad_char, total_len - length));
+    compare(&padded, cover_text, character_set)
+}
+
+/// Returns the largest word count that decodes to a distinct character of a character set.
+///
+/// This is the number of characters in the character set: a sentence of `n` words decodes to its `n`-th
+/// character, and longer sentences wrap around to its start in [`decode`]. Together with a limit on how many
+/// words a sentence can realistically hold, this tells whether a character set is practical before running
+/// [`compare_with_max_words`].
+///
+/// # Arguments
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `usize` - The largest representable word count, or 0 for an empty character set.
+///
+/// # Examples
+/// ```
+/// use stego_wps::max_representable_code;
+///
+/// assert_eq!(max_representable_code("ABCDEFGHIJKLMNOPQRSTUVWXYZ"), 26);
+/// assert_eq!(max_representable_code("αβγ"), 3);
+/// ```
+#[must_use]
+pub fn max_representable_code(character_set: &str) -> usize {
+    character_set.chars().count()
+}
+
+/// Returns the code of a character: its 1-based position in a character set.
+///
+/// A sentence with this many words decodes to `c`, which makes this the building block of [`compare`] for custom
+/// pipelines. [`code_to_char`] is its inverse. If the character appears more than once in the character set, the
+/// position of its last occurrence is returned, matching [`compare`].
+///
+/// # Arguments
+/// * `c` - The character to look up.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Some(usize)` - The 1-based position of `c` in the character set.
+/// * `None` - If `c` is not in the character set.
+///
+/// # Examples
+/// ```
+/// use stego_wps::char_to_code;
+///
+/// assert_eq!(char_to_code('A', "ABC"), Some(1));
+/// assert_eq!(char_to_code('C', "ABC"), Some(3));
+/// assert_eq!(char_to_code('D', "ABC"), None);
+/// ```
+#[must_use]
+pub fn char_to_code(c: char, character_set: &str) -> Option<usize> {
+    character_set
+        .chars()
+        .enumerate()
+        .filter(|&(_, x)| x == c)
+        .last()
+        .map(|(i, _)| i + 1)
+}
+
+/// Returns the character for a code: the character at the code's 1-based position in a character set.
+///
+/// This is the inverse of [`char_to_code`]. Unlike [`decode`], it does not skip zero codes or wrap codes larger
+/// than the character set around, so that callers can apply their own modular arithmetic first.
+///
+/// # Arguments
+/// * `code` - The 1-based position of the character.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Some(char)` - The character at position `code`.
+/// * `None` - If `code` is `0` or larger than the number of characters in the character set.
+///
+/// # Examples
+/// ```
+/// use stego_wps::code_to_char;
+///
+/// assert_eq!(code_to_char(1, "ABC"), Some('A'));
+/// assert_eq!(code_to_char(3, "ABC"), Some('C'));
+/// assert_eq!(code_to_char(0, "ABC"), None);
+/// assert_eq!(code_to_char(4, "ABC"), None);
+/// ```
+#[must_use]
+pub fn code_to_char(code: usize, character_set: &str) -> Option<char> {
+    character_set.chars().nth(code.checked_sub(1)?)
+}
+
+/// Lists, for each character of a character set, every word count up to `max_count` that [`decode`] maps to it.
+///
+/// Since [`decode`] wraps codes larger than the character set, several word counts decode to the same
+/// character: with the 26 letters of the alphabet, a 27-word sentence decodes to `A` just like a 1-word
+/// sentence. This diagnostic shows those collisions for the counts `1..=max_count`. The counts of each character
+/// are in ascending order. The result is a `BTreeMap` rather than a `HashMap`, so this also works without `std`.
+///
+/// # Arguments
+/// * `character_set` - A string slice (`&str`) representing the character set used for decoding.
+/// * `max_count` - The largest word count to consider.
+///
+/// # Returns
+/// * `BTreeMap<char, Vec<usize>>` - The word counts decoding to each character. Empty if the character set is
+///   empty or `max_count` is `0`.
+///
+/// # Examples
+/// ```
+/// use stego_wps::colliding_codes;
+///
+/// let collisions = colliding_codes("ABC", 7);
+/// assert_eq!(collisions[&'A'], vec![1, 4, 7]);
+/// assert_eq!(collisions[&'C'], vec![3, 6]);
+/// ```
+#[must_use]
+pub fn colliding_codes(character_set: &str, max_count: usize) -> BTreeMap<char, Vec<usize>> {
+    let charset: Vec<char> = character_set.chars().collect();
+    let mut collisions: BTreeMap<char, Vec<usize>> = BTreeMap::new();
+    if charset.is_empty() {
+        return collisions;
+    }
+
+    for count in 1..=max_count {
+        collisions
+            .entry(charset[(count - 1) % charset.len()])
+            .or_default()
+            .push(count);
+    }
+    collisions
+}
+
+/// Compares a secret message with a cover text, failing if a sentence would need too many words.
+///
+/// A character late in a long character set needs a long sentence: with the letters of the alphabet, every `Z`
+/// needs a 26-word sentence. This function fails with `CompareError::SentenceTooLong` when any sentence would
+/// need more than `max_words` words, which makes an impractical character set or secret message show up before
+/// the cover text is rewritten. Otherwise, it returns exactly what [`compare`] returns.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `max_words` - The largest number of words a single sentence may have.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` with one change per sentence of the cover text.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`], and additionally:
+/// - If a character of the secret message needs a sentence of more than `max_words` words,
+///   a `CompareError::SentenceTooLong` error is returned for the first such sentence.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare_with_max_words, CompareError};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "This is a sentence. And another one.";
+/// assert_eq!(
+///     compare_with_max_words("HELLO", cover_text, character_set, 12),
+///     Err(CompareError::SentenceTooLong { sentence: 4, words: 15, max: 12 })
+/// );
+/// assert!(compare_with_max_words("HELLO", cover_text, character_set, 15).is_ok());
+/// ```
+pub fn compare_with_max_words(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    max_words: usize,
+) -> Result<Vec<isize>, CompareError> {
+    let counts = message_to_counts(secret_message, character_set)?;
+    if let Some((sentence, &words)) = counts
+        .iter()
+        .enumerate()
+        .find(|(_, &words)| words > max_words)
+    {
+        warn!(
+            "Sentence {} needs {words} words, more than the maximum of {max_words}",
+            sentence + 1
+        );
+        return Err(CompareError::SentenceTooLong {
+            sentence,
+            words,
+            max: max_words,
+        });
+    }
+
+    compare(secret_message, cover_text, character_set)
+}
+
+/// Compares a secret message with a cover text like [`compare`], spreading the work across threads.
+///
+/// This is only available with the `rayon` feature. The character lookups for the secret message and the
+/// per-sentence change computation run on rayon's parallel iterators, which pays off for secret messages and
+/// cover texts thousands of sentences long. The returned changes are identical to those of [`compare`], in the
+/// same order. If several characters of the secret message are missing from the character set, the one reported
+/// in `CharacterNotFound` is not necessarily the first.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_parallel};
+///
+/// let cover_text = "This is a sentence. Another one here.";
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// assert_eq!(
+///     compare_parallel("HELLO", cover_text, character_set),
+///     compare("HELLO", cover_text, character_set),
+/// );
+/// ```
+#[cfg(feature = "rayon")]
+pub fn compare_parallel(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<isize>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let charset_map = charset_positions(character_set)?;
+    let cover_encoded = encode(cover_text).map_err(CompareError::EncodingError)?;
+
+    let secret_chars: Vec<char> = secret_message.chars().collect();
+    let secret_positions = secret_chars
+        .par_iter()
+        .map(|c| {
+            charset_map
+                .get(c)
+                .copied()
+                .ok_or(CompareError::CharacterNotFound(*c))
+        })
+        .collect::<Result<Vec<isize>, _>>()?;
+
+    (0..secret_positions.len().max(cover_encoded.len()))
+        .into_par_iter()
+        .map(|i| {
+            let count = cover_encoded
+                .get(i)
+                .map_or(Ok(0), |&count| isize::try_from(count))
+                .map_err(|_| CompareError::ValueOutOfRange)?;
+            Ok(secret_positions.get(i).copied().unwrap_or(0) - count)
+        })
+        .collect()
+}
+
+/// Encodes a given text into a vector of word counts per sentence like [`encode`], counting words across threads.
+///
+/// This is only available with the `rayon` feature. The text is split into sentences on the calling thread, then
+/// the words of each sentence are counted on rayon's parallel iterators, which pays off for multi-megabyte
+/// texts. The returned word counts are identical to those of [`encode`], in the same order.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be encoded.
+///
+/// # Returns
+/// * `Ok(Vec<usize>)` - A vector of word counts per sentence if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns the same errors as [`encode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::{encode, encode_parallel};
+///
+/// let text = "This is a sentence. Another one here.";
+/// assert_eq!(encode_parallel(text), encode(text));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn encode_parallel(txt: &str) -> Result<Vec<usize>, EncodingError> {
+    let options = EncodeOptions::default();
+    check_ascii(txt, options.allow_non_ascii)?;
+
+    let sentences: Vec<&str> = sentence_ranges(txt, &options)
+        .map(|(range, _)| &txt[range])
+        .collect();
+    let encoded: Vec<usize> = sentences
+        .par_iter()
+        .map(|sentence| count_words(sentence, &options))
+        .filter(|&count| count > 0)
+        .collect();
+
+    if encoded.is_empty() {
+        return Err(no_sentences_error(txt.trim().is_empty()));
+    }
+
+    debug!("Encoded text: {encoded:?}");
+    Ok(encoded)
+}
+
+/// Compares a secret message with a cover text, packing several characters into each sentence.
+///
+/// This is the encoder for [`decode_packed`]. The secret message is split into chunks of `chars_per_sentence`
+/// characters, and each chunk is turned into the word count that [`decode_packed`] reads back: the chunk's
+/// 0-based character set positions form a base-`N` number (most significant first), plus one. The message then
+/// needs only `ceil(len / chars_per_sentence)` sentences, at the cost of much longer sentences: up to
+/// `N.pow(chars_per_sentence)` words each.
+///
+/// If the length of the secret message is not a multiple of `chars_per_sentence`, the last chunk is padded with
+/// the first character of the character set, so the decoded message ends with up to `chars_per_sentence - 1`
+/// extra copies of that character. The changes are laid out like those of [`compare`].
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `chars_per_sentence` - The number of characters packed into each sentence.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - `chars_per_sentence` is `0` (`DecodingError::ZeroCharsPerSentence`).
+/// - `N.pow(chars_per_sentence)` does not fit in a `usize`, where `N` is the number of characters in the
+///   character set (`DecodingError::CharsPerSentenceTooLarge`).
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - A packed word count does not fit in an `isize` (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{apply_changes, compare_packed, decode_packed, encode};
+///
+/// let character_set = "ABCD";
+/// let cover_text = "One two three. Four five.";
+/// let changes = compare_packed("DCBA", cover_text, character_set, 2).expect("Failed to compare");
+/// let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply changes");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode_packed(&encoded, character_set, 2).expect("Failed to decode"), "DCBA");
+/// ```
+#[doc(alias = "compare_base")]
+pub fn compare_packed(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    chars_per_sentence: usize,
+) -> Result<Vec<isize>, CompareError> {
+    packed_max_code(character_set.chars().count(), chars_per_sentence)?;
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let cover_encoded = encode(cover_text)?;
+    let base = character_set.chars().count();
+    let positions = charset_indices(character_set);
+
+    let secret: Vec<char> = secret_message.chars().collect();
+    let targets = secret
+        .chunks(chars_per_sentence)
+        .map(|chunk| {
+            let mut value: usize = 0;
+            for i in 0..chars_per_sentence {
+                let digit = match chunk.get(i) {
+                    Some(&c) => positions
+                        .get(&c)
+                        .copied()
+                        .ok_or(CompareError::CharacterNotFound(c))?,
+                    None => 0,
+                };
+                value = value
+                    .checked_mul(base)
+                    .and_then(|v| v.checked_add(digit))
+                    .ok_or(CompareError::ValueOutOfRange)?;
+            }
+            value
+                .checked_add(1)
+                .and_then(|v| isize::try_from(v).ok())
+                .ok_or(CompareError::ValueOutOfRange)
+        })
+        .collect::<Result<Vec<isize>, _>>()?;
+
+    changes_for_targets(&targets, &cover_encoded)
+}
+
+/// Compares a secret message with a cover text, matching characters against the character set
+/// without regard to case.
+///
+/// This behaves like [`compare`], except that both the character set and the characters of the secret
+/// message are lowercased before matching, so `'h'` maps to the position of `'H'` in the character set.
+/// If the character set contains both cases of a letter (e.g. `'a'` and `'A'`), the last occurrence wins, like a
+/// duplicate character does in [`compare`].
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - A character in the secret message is not found in the character set, in any case (`CharacterNotFound`).
+/// - Value conversion to `isize` is out of range (`ValueOutOfRange`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_case_insensitive};
+///
+/// let cover_text = "This is a sentence. Another one here.";
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// assert_eq!(
+///     compare_case_insensitive("hi", cover_text, character_set),
+///     compare("HI", cover_text, character_set),
+/// );
+/// ```
+pub fn compare_case_insensitive(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<Vec<isize>, CompareError> {
+    if secret_message.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut charset_map: BTreeMap<char, isize> = BTreeMap::new();
+    for (i, c) in character_set.chars().enumerate() {
+        let val = isize::try_from(i).map_err(|_| CompareError::ValueOutOfRange)?;
+        charset_map.insert(fold_case(c), val + 1);
+    }
+
+    compare_with_lookup(secret_message, cover_text, |c| {
+        charset_map.get(&fold_case(c)).copied()
+    })
+}
+
+/// Options controlling how [`compare_with_options`] matches the secret message against the character set and
+/// the cover text.
+///
+/// `CompareOptions::default()` matches the behavior of [`compare`] exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompareOptions {
+    /// Match characters without regard to case, like [`compare_case_insensitive`], so `"hello"` and `"HELLO"`
+    /// need the same word counts.
+    pub case_insensitive: bool,
+    /// Fail with `CompareError::InsufficientCapacity` when the secret message has more characters than the cover
+    /// text has sentences, like [`compare_strict`], instead of appending changes for new sentences.
+    pub strict_capacity: bool,
+}
+
+/// Compares a secret message with a cover text, using the given [`CompareOptions`].
+///
+/// With default options this behaves exactly like [`compare`]. Each option adjusts how the characters of the
+/// secret message are looked up in the character set or how a short cover text is handled, see
+/// [`CompareOptions`] for the available settings.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be encoded.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `options` - The [`CompareOptions`] to compare with.
+///
+/// # Returns
+/// * `Ok(Vec<isize>)` - A vector of `isize` where each element represents the necessary change in word count for each sentence.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns the same errors as [`compare`], and additionally:
+/// - If `strict_capacity` is set and the secret message has more characters than the cover text has sentences,
+///   a `CompareError::InsufficientCapacity` error is returned.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare, compare_with_options, CompareError, CompareOptions};
+///
+/// let cover_text = "This is a sentence. Another one here.";
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let options = CompareOptions {
+///     case_insensitive: true,
+///     ..CompareOptions::default()
+/// };
+/// assert_eq!(
+///     compare_with_options("Hi", cover_text, character_set, &options),
+///     compare("HI", cover_text, character_set),
+/// );
+/// let options = CompareOptions {
+///     strict_capacity: true,
+///     ..CompareOptions::default()
+/// };
+/// assert_eq!(
+///     compare_with_options("HEY", cover_text, character_set, &options),
+///     Err(CompareError::InsufficientCapacity { have: 2, need: 3 })
+/// );
+/// ```
+pub fn compare_with_options(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    options: &CompareOptions,
+) -> Result<Vec<isize>, CompareError> {
+    if options.strict_capacity {
+        check_capacity(secret_message, cover_text)?;
+    }
+
+    if options.case_insensitive {
+        compare_case_insensitive(secret_message, cover_text, character_set)
+    } else {
+        compare(secret_message, cover_text, character_set)
+    }
+}
+
+/// Maps every character of the character set to its 0-based position. If a character appears more than once,
+/// the last occurrence wins, like in [`charset_positions`].
+fn charset_indices(character_set: &str) -> BTreeMap<char, usize> {
+    character_set
+        .chars()
+        .enumerate()
+        .map(|(i, c)| (c, i))
+        .collect()
+}
+
+/// Maps every character of the character set to its 1-based position. If a character appears more than once,
+/// the last occurrence wins.
+fn charset_positions(character_set: &str) -> Result<BTreeMap<char, isize>, CompareError> {
+    character_set
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            isize::try_from(i).map_or(Err(CompareError::ValueOutOfRange), |val| Ok((c, val + 1)))
+        })
+        .collect()
+}
+
+/// Computes the word count changes for `secret_message`, resolving each character with `lookup`.
+fn compare_with_lookup(
+    secret_message: &str,
+    cover_text: &str,
+    lookup: impl Fn(char) -> Option<isize>,
+) -> Result<Vec<isize>, CompareError> {
+    let cover_encoded = encode(cover_text).map_err(CompareError::EncodingError)?;
+
+    let secret_positions = secret_message
+        .chars()
+        .map(|c| lookup(c).ok_or(CompareError::CharacterNotFound(c)))
+        .collect::<Result<Vec<isize>, _>>()?;
+
+    changes_for_targets(&secret_positions, &cover_encoded)
+}
+
+/// Computes the word count changes that turn the sentences of `cover_encoded` into `secret_positions`.
+fn changes_for_targets(
+    secret_positions: &[isize],
+    cover_encoded: &[usize],
+) -> Result<Vec<isize>, CompareError> {
+    let mut changes = vec![0; cover_encoded.len()];
+    for (i, &pos) in secret_positions.iter().enumerate() {
+        if i < cover_encoded.len() {
+            changes[i] = word_delta(pos, cover_encoded[i])?;
+        } else {
+            changes.push(pos);
+        }
+    }
+
+    for i in secret_positions.len()..cover_encoded.len() {
+        changes[i] = word_delta(0, cover_encoded[i])?;
+    }
+
+    Ok(changes)
+}
+
+/// Returns `target - count`, computed as an `i128` so that only a difference that doesn't fit an `isize` fails,
+/// even when `count` itself is larger than `isize::MAX`.
+fn word_delta(target: isize, count: usize) -> Result<isize, CompareError> {
+    i128::try_from(target)
+        .ok()
+        .zip(i128::try_from(count).ok())
+        .and_then(|(target, count)| isize::try_from(target - count).ok())
+        .ok_or(CompareError::ValueOutOfRange)
+}
+
+/// Verifies that a cover text carries a secret message.
+///
+/// This encodes the cover text, decodes the result with the character set and checks that the decoded string
+/// starts with the secret message. It catches mismatches such as wrong sentence counts or a truncated cover
+/// text in a single step, before the cover text is shipped.
+///
+/// Cover texts with more sentences than the secret message has characters still verify successfully: the
+/// characters decoded from the trailing sentences are ignored, since only the decoded prefix is compared. Use
+/// [`verify_exact`] instead to reject such trailing sentences.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the expected secret message.
+/// * `cover_text` - A string slice (`&str`) representing the cover text to verify.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(bool)` - `true` if the cover text decodes to a string starting with the secret message.
+/// * `Err(CompareError)` - An error if the cover text cannot be encoded or decoded.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - The encoded cover text cannot be decoded with the character set (`DecodingError`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::verify;
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "One two three four five six seven eight. One two three four five six seven eight nine.";
+/// assert!(verify("HI", cover_text, character_set).expect("Failed to verify"));
+/// assert!(!verify("HA", cover_text, character_set).expect("Failed to verify"));
+/// ```
+pub fn verify(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<bool, CompareError> {
+    let encoded = encode(cover_text)?;
+    let decoded = decode(&encoded, character_set)?;
+    Ok(decoded.starts_with(secret_message))
+}
+
+/// Verifies that a cover text carries exactly a secret message, and nothing more.
+///
+/// This behaves like [`verify`], except that the decoded string must equal the secret message instead of just
+/// starting with it, so a cover text with trailing sentences beyond the end of the secret message fails to
+/// verify. The comparison is case-sensitive.
+///
+/// Use `verify` when the cover text may legitimately continue past the message, and `verify_exact` when the
+/// whole cover text was built for the message, for example with [`compare_and_extend`] or [`generate_cover`].
+/// Both take the secret message first and the cover text second, like [`compare`], and both return a
+/// [`CompareError`], since encoding the cover text can fail as well as decoding it.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the expected secret message.
+/// * `cover_text` - A string slice (`&str`) representing the cover text to verify.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+///
+/// # Returns
+/// * `Ok(bool)` - `true` if the cover text decodes to exactly the secret message.
+/// * `Err(CompareError)` - An error if the cover text cannot be encoded or decoded.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text cannot be successfully encoded (`EncodingError`).
+/// - The encoded cover text cannot be decoded with the character set (`DecodingError`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{verify, verify_exact};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "One two three four five six seven eight. One two three four five six seven eight nine. One.";
+/// assert!(verify("HI", cover_text, character_set).expect("Failed to verify"));
+/// assert!(!verify_exact("HI", cover_text, character_set).expect("Failed to verify"));
+/// assert!(verify_exact("HIA", cover_text, character_set).expect("Failed to verify"));
+/// ```
+pub fn verify_exact(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+) -> Result<bool, CompareError> {
+    let encoded = encode(cover_text)?;
+    let decoded = decode(&encoded, character_set)?;
+    Ok(decoded == secret_message)
+}
+
+/// Returns how many characters of a secret message a cover text can hold.
+///
+/// Each valid sentence of the cover text carries exactly one character, so the capacity is the number of
+/// sentences [`encode`] finds. A secret message longer than the capacity makes [`compare`] ask for new
+/// sentences to be added to the cover text, which [`compare_and_extend`] does automatically, while
+/// [`compare_strict`] rejects it. Decoding a cover text that was not extended yields only the first
+/// `capacity` characters of the secret message.
+///
+/// # Arguments
+/// * `cover_text` - A string slice (`&str`) representing the cover text.
+///
+/// # Returns
+/// * `Ok(usize)` - The number of characters the cover text can hold.
+/// * `Err(EncodingError)` - An `EncodingError` if the cover text cannot be encoded.
+///
+/// # Errors
+/// This function returns the same errors as [`encode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::capacity;
+///
+/// let secret_message = "HI";
+/// let cover_text = "This is a sentence. Another one here.";
+/// assert!(secret_message.chars().count() <= capacity(cover_text).expect("Failed to encode"));
+/// ```
+pub fn capacity(cover_text: &str) -> Result<usize, EncodingError> {
+    encode(cover_text).map(|encoded| encoded.len())
+}
+
+/// Summary statistics of the sentence lengths of a text, as returned by [`text_stats`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStats {
+    /// The number of sentences, that is the number of counts [`encode`] returns.
+    pub sentence_count: usize,
+    /// The number of words across all sentences.
+    pub total_words: usize,
+    /// The word count of the shortest sentence.
+    pub min_words: usize,
+    /// The word count of the longest sentence.
+    pub max_words: usize,
+    /// The average word count per sentence.
+    pub mean_words: f64,
+}
+
+/// Summarizes the sentence lengths of a text before encoding.
+///
+/// The text is split into sentences exactly like [`encode`] does, but instead of listing the word count of each
+/// sentence, the counts are aggregated into a [`TextStats`]. This gives a quick idea of whether a cover text has
+/// enough sentences, and sentences of a suitable length, for a secret message.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be analyzed.
+///
+/// # Returns
+/// * `Ok(TextStats)` - The statistics of the sentence lengths if encoding is successful.
+/// * `Err(EncodingError)` - An `EncodingError` in case of non-ASCII input or no valid sentences.
+///
+/// # Errors
+/// This function returns the same errors as [`encode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::text_stats;
+///
+/// let stats = text_stats("One two three. One. One two.").expect("Failed to encode");
+/// assert_eq!(stats.sentence_count, 3);
+/// assert_eq!((stats.min_words, stats.max_words), (1, 3));
+/// assert!((stats.mean_words - 2.0).abs() < f64::EPSILON);
+/// ```
+// Word counts large enough to lose precision as an `f64` are not a realistic concern.
+#[allow(clippy::cast_precision_loss)]
+pub fn text_stats(txt: &str) -> Result<TextStats, EncodingError> {
+    let encoded = encode(txt)?;
+    let total_words: usize = encoded.iter().sum();
+    let stats = TextStats {
+        sentence_count: encoded.len(),
+        total_words,
+        min_words: encoded.iter().copied().min().unwrap_or(0),
+        max_words: encoded.iter().copied().max().unwrap_or(0),
+        mean_words: total_words as f64 / encoded.len() as f64,
+    };
+
+    debug!("Text stats: {stats:?}");
+    Ok(stats)
+}
+
+/// Estimates how likely a text is to carry a WPS payload, as a score between 0 and 1.
+///
+/// This is a heuristic, not proof: a high score only means the sentence lengths look more like a hidden message
+/// than like natural prose, and short or unusual texts are easily misjudged either way. Each sentence length is
+/// weighed against two models with equal prior odds. The payload model assumes a 26-letter character set, so a
+/// payload sentence has between 1 and 26 words, all equally likely. The natural model is a gamma distribution with
+/// a mean of 16 words, which roughly fits English prose: very short sentences are rare, and longer ones are common.
+/// The score is the average probability, over all sentences, that the payload model produced the sentence.
+///
+/// Sentences of more than 26 words therefore count as natural, while runs of one-, two- or three-word
+/// sentences, which hidden messages produce for every `A`, `B` and `C`, push the score towards 1.
+///
+/// # Arguments
+/// * `txt` - A string slice (`&str`) representing the text to be analyzed.
+///
+/// # Returns
+/// * `Ok(f64)` - The score, from 0 (natural) to 1 (likely carrying a payload).
+/// * `Err(EncodingError)` - An `EncodingError` if the text cannot be encoded.
+///
+/// # Errors
+/// This function returns the same errors as [`encode`].
+///
+/// # Examples
+/// ```
+/// use stego_wps::suspicion_score;
+///
+/// let score = suspicion_score("A. Bc de. F.").expect("Failed to encode");
+/// assert!(score > 0.9);
+/// ```
+pub fn suspicion_score(txt: &str) -> Result<f64, EncodingError> {
+    /// The probability of a sentence length under the payload model: uniform over 1 to 26 words.
+    const PAYLOAD_PROBABILITY: f64 = 1.0 / 26.0;
+    /// `e^(-1/4)`, the per-word decay of the natural model.
+    const NATURAL_DECAY: f64 = 0.778_800_783_071_404_9;
+
+    let encoded = encode(txt)?;
+    let (total, sentences) = encoded
+        .iter()
+        .fold((0.0, 0.0), |(total, sentences), &words| {
+            let payload = if words <= 26 {
+                PAYLOAD_PROBABILITY
+            } else {
+                0.0
+            };
+            // Gamma density with shape 4 and scale 4: w^3 * e^(-w / 4) / (3! * 4^4).
+            let (w, decay) = (0..words.min(27))
+                .fold((0.0, 1.0), |(w, decay), _| (w + 1.0, decay * NATURAL_DECAY));
+            let natural = w * w * w * decay / 1536.0;
+            (total + payload / (payload + natural), sentences + 1.0)
+        });
+
+    let score = total / sentences;
+    debug!("Suspicion score: {score}");
+    Ok(score)
+}
+
+/// Applies a vector of word count changes to a cover text, producing the steganographic text.
+///
+/// This function is the counterpart of [`compare`]. It walks the sentences of the cover text in order
+/// and adjusts each one by the corresponding entry in `changes`: positive values append `filler_word`
+/// that many times to the end of the sentence, while negative values remove that many trailing words.
+/// Sentences keep their original terminators and surrounding whitespace. Entries of `changes` beyond the
+/// number of sentences in the cover text are appended as new sentences made of `filler_word` and ended
+/// with a `.`; sentences without a corresponding entry are left untouched.
+///
+/// A negative change that removes every word of a sentence drops the sentence, including its terminator,
+/// which matches how [`compare`] asks for surplus cover sentences to be removed. A negative change larger
+/// than the number of words in the sentence is an error.
+///
+/// # Arguments
+/// * `cover_text` - A string slice (`&str`) representing the cover text to be modified.
+/// * `changes` - A slice of `isize` with the change in word count for each sentence, as returned by [`compare`].
+/// * `filler_word` - A string slice (`&str`) with the word used to pad sentences.
+///
+/// # Returns
+/// * `Ok(String)` - The modified cover text.
+/// * `Err(CompareError)` - An error if the cover text cannot be encoded, the filler word is invalid,
+///   or a sentence does not have enough words to remove.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text is not ASCII-encoded (`EncodingError`).
+/// - The filler word has no letter or digit, is not ASCII, or contains whitespace or a sentence terminator (`InvalidFillerWord`).
+/// - A negative change exceeds the number of words in its sentence (`InsufficientWords`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{apply_changes, compare, decode, encode};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "This is a sentence. And another one!";
+/// let changes = compare("HI", cover_text, character_set).expect("Failed to compare");
+/// let stego_text = apply_changes(cover_text, &changes, "very").expect("Failed to apply changes");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode(&encoded, character_set).expect("Failed to decode"), "HI");
+/// ```
+pub fn apply_changes(
+    cover_text: &str,
+    changes: &[isize],
+    filler_word: &str,
+) -> Result<String, CompareError> {
+    apply_changes_with_fillers(cover_text, changes, &[filler_word])
+}
+
+/// Applies `changes` to `cover_text` like [`apply_changes`], drawing filler words from `filler_words`
+/// cyclically across the whole text.
+fn apply_changes_with_fillers(
+    cover_text: &str,
+    changes: &[isize],
+    filler_words: &[&str],
+) -> Result<String, CompareError> {
+    check_ascii(cover_text, false)?;
+    if filler_words.is_empty() {
+        warn!("Word pool is empty");
+        return Err(CompareError::EmptyWordPool);
+    }
+    for word in filler_words {
+        validate_filler_word(word)?;
+    }
+
+    let mut fillers = filler_words.iter().cycle();
+    let mut output = String::with_capacity(cover_text.len());
+    let mut remaining = changes.iter();
+    let mut sentence = 0;
+    let mut unterminated = false;
+
+    for (body, terminator) in split_sentences_with_terminators(cover_text) {
+        let available = words_of(body).count();
+        let change = if available == 0 {
+            0
+        } else {
+            remaining.next().copied().unwrap_or(0)
+        };
+
+        if change < 0 {
+            let requested = change.unsigned_abs();
+            if requested > available {
+                return Err(CompareError::InsufficientWords {
+                    sentence,
+                    available,
+                    requested,
+                });
+            }
+            if requested == available {
+                sentence += 1;
+                continue;
+            }
+            output.push_str(truncate_words(body, available - requested));
+        } else {
+            let trimmed = body.trim_end();
+            output.push_str(trimmed);
+            for word in fillers.by_ref().take(change.unsigned_abs()) {
+                output.push(' ');
+                output.push_str(word);
+            }
+            output.push_str(&body[trimmed.len()..]);
+        }
+
+        if available > 0 {
+            sentence += 1;
+        }
+        unterminated = terminator.is_none() && available > 0;
+        if let Some(t) = terminator {
+            output.push(t);
+        }
+    }
+
+    for &change in remaining {
+        let words = usize::try_from(change).map_err(|_| CompareError::InsufficientWords {
+            sentence,
+            available: 0,
+            requested: change.unsigned_abs(),
+        })?;
+        sentence += 1;
+        if words == 0 {
+            continue;
+        }
+        if unterminated {
+            output.push('.');
+            unterminated = false;
+        }
+        if !output.is_empty() && !output.ends_with(char::is_whitespace) {
+            output.push(' ');
+        }
+        let sentence_words: Vec<&str> = fillers.by_ref().take(words).copied().collect();
+        output.push_str(&sentence_words.join(" "));
+        output.push('.');
+    }
+
+    debug!("Applied changes: {output:?}");
+    Ok(output)
+}
+
+/// Generates the steganographic text hiding a secret message in a cover text.
+///
+/// This runs [`compare`] and applies the resulting changes to the cover text like [`apply_changes`], except that
+/// the words added to lengthen sentences are drawn from `filler_words` cyclically across the whole text, which
+/// reads less repetitively than a single filler word. Sentences keep their original terminators, words are
+/// trimmed from the end of sentences that are too long, cover sentences beyond the end of the secret message
+/// are removed, and missing sentences are appended. The result satisfies
+/// `decode(&encode(&result)?, character_set)? == secret_message` for a non-empty secret message.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be hidden.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `filler_words` - A slice of words used to lengthen sentences and fill new ones.
+///
+/// # Returns
+/// * `Ok(String)` - The steganographic text.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process or the filler words are
+///   invalid.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text is not ASCII-encoded or has no valid sentences (`EncodingError`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+/// - `filler_words` is empty (`EmptyWordPool`).
+/// - A filler word has no letter or digit, is not ASCII, or contains whitespace or a sentence terminator (`InvalidFillerWord`).
+/// - A sentence would be left with fewer words than it has (`InsufficientWords`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode, encode, generate_stego_text};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = "This is a sentence. And another one!";
+/// let stego_text = generate_stego_text("HI", cover_text, character_set, &["quite", "very"])
+///     .expect("Failed to generate");
+/// assert!(stego_text.starts_with("This is a sentence quite very quite very. And another one quite very"));
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode(&encoded, character_set).expect("Failed to decode"), "HI");
+/// ```
+pub fn generate_stego_text(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    filler_words: &[&str],
+) -> Result<String, CompareError> {
+    let changes = compare(secret_message, cover_text, character_set)?;
+    apply_changes_with_fillers(cover_text, &changes, filler_words)
+}
+
+/// Rewrites a single sentence to an exact number of words.
+///
+/// If the sentence has fewer than `target_words` words, words from `filler_words` are appended, cycling through
+/// them as needed. If it has more, trailing words are removed. A sentence that already has `target_words` words
+/// is returned unchanged. A terminator (`.`, `!` or `?`) ending the sentence is kept at its end, and trailing
+/// whitespace is removed. This is the building block for crafting a cover text by hand, one sentence at a time.
+///
+/// # Arguments
+/// * `sentence` - A string slice (`&str`) holding a single sentence, with or without its terminator.
+/// * `target_words` - The number of words the sentence must have.
+/// * `filler_words` - A slice of words used to lengthen the sentence.
+///
+/// # Returns
+/// * `Ok(String)` - The sentence with exactly `target_words` words.
+/// * `Err(CompareError)` - An error if the target is zero or the filler words are invalid.
+///
+/// # Errors
+/// This function returns an error if:
+/// - `target_words` is `0`, since a sentence without words is not counted (`ZeroTargetWords`).
+/// - `filler_words` is empty (`EmptyWordPool`).
+/// - A filler word has no letter or digit, is not ASCII, or contains whitespace or a sentence terminator (`InvalidFillerWord`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::pad_sentence;
+///
+/// let filler_words = ["truly", "quite"];
+/// assert_eq!(pad_sentence("It is nice.", 6, &filler_words).expect("Failed to pad"), "It is nice truly quite truly.");
+/// assert_eq!(pad_sentence("It is nice!", 2, &filler_words).expect("Failed to pad"), "It is!");
+/// ```
+pub fn pad_sentence(
+    sentence: &str,
+    target_words: usize,
+    filler_words: &[&str],
+) -> Result<String, CompareError> {
+    if target_words == 0 {
+        warn!("Target word count is zero");
+        return Err(CompareError::ZeroTargetWords);
+    }
+    if filler_words.is_empty() {
+        warn!("Word pool is empty");
+        return Err(CompareError::EmptyWordPool);
+    }
+    for word in filler_words {
+        validate_filler_word(word)?;
+    }
+
+    let trimmed = sentence.trim_end();
+    let (body, terminator) = match trimmed.chars().next_back() {
+        Some(c) if DEFAULT_TERMINATORS.contains(&c) => {
+            (&trimmed[..trimmed.len() - c.len_utf8()], Some(c))
+        }
+        _ => (trimmed, None),
+    };
+
+    let available = words_of(body).count();
+    let mut padded = truncate_words(body, target_words).trim_end().to_string();
+    for word in filler_words
+        .iter()
+        .cycle()
+        .take(target_words.saturating_sub(available))
+    {
+        if !padded.is_empty() {
+            padded.push(' ');
+        }
+        padded.push_str(word);
+    }
+    padded.extend(terminator);
+
+    debug!("Padded sentence: {padded:?}");
+    Ok(padded)
+}
+
+/// Generates a synthetic cover text that hides a secret message.
+///
+/// For each character of the secret message, this emits one sentence containing exactly as many words as the
+/// character's 1-based position in the character set, ending with a period. Words are drawn from `word_pool`
+/// cyclically across the whole text, so the result satisfies
+/// `decode(&encode(&result)?, character_set)? == secret_message`. Since positions are 1-based, every sentence
+/// has at least one word. An empty secret message produces an empty string.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be hidden.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `word_pool` - A slice of words used to build the sentences.
+///
+/// # Returns
+/// * `Ok(String)` - The generated cover text.
+/// * `Err(CompareError)` - An error if a character is missing from the character set or the word pool is invalid.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The word pool is empty (`EmptyWordPool`).
+/// - A word in the pool has no letter or digit, is not ASCII, or contains whitespace or a sentence terminator (`InvalidFillerWord`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::generate_cover;
+///
+/// let cover_text = generate_cover("CAB", "ABC", &["lorem", "ipsum"]).expect("Failed to generate");
+/// assert_eq!(cover_text, "lorem ipsum lorem. ipsum. lorem ipsum.");
+/// ```
+pub fn generate_cover(
+    secret_message: &str,
+    character_set: &str,
+    word_pool: &[&str],
+) -> Result<String, CompareError> {
+    if word_pool.is_empty() {
+        warn!("Word pool is empty");
+        return Err(CompareError::EmptyWordPool);
+    }
+    for word in word_pool {
+        validate_filler_word(word)?;
+    }
+
+    let positions = charset_indices(character_set);
+    let mut words = word_pool.iter().cycle();
+    let mut sentences = Vec::with_capacity(secret_message.len());
+    for c in secret_message.chars() {
+        let position = positions
+            .get(&c)
+            .copied()
+            .ok_or(CompareError::CharacterNotFound(c))?;
+        let sentence: Vec<&str> = words.by_ref().take(position + 1).copied().collect();
+        sentences.push(format!("{}.", sentence.join(" ")));
+    }
+
+    let cover_text = sentences.join(" ");
+    debug!("Generated cover text: {cover_text:?}");
+    Ok(cover_text)
+}
+
+/// Builds a cover text by repeating a template word to the given word counts.
+///
+/// The output contains one sentence per count, in order. Each sentence is `template_word` repeated `count`
+/// times and separated by single spaces, immediately followed by `terminator`, and consecutive sentences are
+/// separated by a single space: `fill_template(&[2, 1], "la", '.')` is `"la la. la."`. A count of 0 emits the
+/// bare terminator, which [`encode`] skips just as [`decode`] skips zero codes. No counts produce an empty
+/// string.
+///
+/// The output round-trips through [`encode`] as long as `template_word` is a single ASCII word without
+/// whitespace or sentence terminators, and `terminator` is one of the [`DEFAULT_TERMINATORS`]. Combined with
+/// [`message_to_counts`], this yields a cover text that decodes to a given secret message.
+///
+/// # Arguments
+/// * `counts` - The word count of each sentence.
+/// * `template_word` - The word repeated to fill each sentence.
+/// * `terminator` - The character ending each sentence.
+///
+/// # Returns
+/// * `String` - The generated cover text.
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode, encode, fill_template, message_to_counts};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let counts = message_to_counts("CAB", character_set).expect("Failed to convert");
+/// let cover_text = fill_template(&counts, "la", '!');
+/// assert_eq!(cover_text, "la la la! la! la la!");
+///
+/// let encoded = encode(&cover_text).expect("Failed to encode");
+/// assert_eq!(decode(&encoded, character_set).expect("Failed to decode"), "CAB");
+/// ```
+#[must_use]
+pub fn fill_template(counts: &[usize], template_word: &str, terminator: char) -> String {
+    let cover_text = counts
+        .iter()
+        .map(|&count| format!("{}{terminator}", vec![template_word; count].join(" ")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    debug!("Filled template: {cover_text:?}");
+    cover_text
+}
+
+/// Builds the shortest cover text made of a single repeated word that hides a secret message.
+///
+/// Each character of the secret message becomes one sentence: `word` repeated as many times as the character's
+/// 1-based position in the character set, followed by a period. With `ABCDE` as the character set, `D` becomes
+/// `"word word word word."`. This is [`message_to_counts`] followed by [`fill_template`], with `word` validated
+/// so that the result always round-trips through [`encode`] and [`decode`]. An empty secret message yields an
+/// empty string.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be hidden.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `word` - The word repeated to fill each sentence.
+///
+/// # Returns
+/// * `Ok(String)` - The generated cover text.
+/// * `Err(CompareError)` - An error if the secret message or `word` cannot be used.
+///
+/// # Errors
+/// This function returns an error if:
+/// - `word` has no letter or digit, is not ASCII, or contains whitespace or a sentence terminator (`InvalidFillerWord`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{decode, encode, synthesize_cover};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let cover_text = synthesize_cover("DAB", character_set, "word").expect("Failed to synthesize");
+/// assert_eq!(cover_text, "word word word word. word. word word.");
+///
+/// let encoded = encode(&cover_text).expect("Failed to encode");
+/// assert_eq!(decode(&encoded, character_set).expect("Failed to decode"), "DAB");
+/// ```
+pub fn synthesize_cover(
+    secret_message: &str,
+    character_set: &str,
+    word: &str,
+) -> Result<String, CompareError> {
+    validate_filler_word(word)?;
+    let counts = message_to_counts(secret_message, character_set)?;
+    Ok(fill_template(&counts, word, '.'))
+}
+
+/// Hides a secret message in a cover text, extending the cover text with new sentences as needed.
+///
+/// This combines [`compare`] and [`apply_changes`] into a single step that returns the complete stego text.
+/// Sentences are padded with `filler_word` or shortened as needed, sentences beyond the end of the secret
+/// message are removed, and if the secret message has more characters than the cover text has sentences, new
+/// sentences made of `filler_word` are appended. A cover text without any valid sentences, such as an empty
+/// one, is replaced by sentences generated from scratch with [`fill_template`]. The result always satisfies
+/// `decode(&encode(&result)?, character_set)? == secret_message`, except for an empty secret message, which
+/// yields an empty string.
+///
+/// # Arguments
+/// * `secret_message` - A string slice (`&str`) representing the secret message to be hidden.
+/// * `cover_text` - A string slice (`&str`) representing the cover text used for encoding.
+/// * `character_set` - A string slice (`&str`) representing the character set used for encoding.
+/// * `filler_word` - The word inserted to lengthen sentences and fill new ones.
+///
+/// # Returns
+/// * `Ok(String)` - The stego text holding the full secret message.
+/// * `Err(CompareError)` - An error if there is a problem in the comparison process.
+///
+/// # Errors
+/// This function returns an error if:
+/// - The cover text is not ASCII-encoded (`EncodingError`).
+/// - The filler word has no letter or digit, is not ASCII, or contains whitespace or a sentence terminator (`InvalidFillerWord`).
+/// - A character in the secret message is not found in the character set (`CharacterNotFound`).
+///
+/// # Examples
+/// ```
+/// use stego_wps::{compare_and_extend, decode, encode};
+///
+/// let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+/// let stego_text = compare_and_extend("CAB", "Hello there. Bye.", character_set, "very")
+///     .expect("Failed to compare");
+/// assert_eq!(stego_text, "Hello there very. Bye. very very.");
+/// let encoded = encode(&stego_text).expect("Failed to encode");
+/// assert_eq!(decode(&encoded, character_set).expect("Failed to decode"), "CAB");
+///
+/// let generated = compare_and_extend("BA", "", character_set, "very").expect("Failed to compare");
+/// assert_eq!(generated, "very very. very.");
+/// ```
+pub fn compare_and_extend(
+    secret_message: &str,
+    cover_text: &str,
+    character_set: &str,
+    filler_word: &str,
+) -> Result<String, CompareError> {
+    validate_filler_word(filler_word)?;
+
+    match compare(secret_message, cover_text, character_set) {
+        Ok(_) if secret_message.is_empty() => Ok(String::new()),
+        Ok(changes) => apply_changes(cover_text, &changes, filler_word),
+        Err(CompareError::EncodingError(
+            EncodingError::NoValidSentences | EncodingError::EmptyInput,
+        )) => {
+            debug!("Cover text has no valid sentences, generating one from scratch");
+            let counts = message_to_counts(secret_message, character_set)?;
+            Ok(fill_template(&counts, filler_word, '.'))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Checks that `word` is a single ASCII word that [`encode`] counts exactly once.
+fn validate_filler_word(word: &str) -> Result<(), CompareError> {
+    if !is_word(word, WordPolicy::default())
+        || !word.is_ascii()
+        || word
+            .chars()
+            .any(|c| c.is_whitespace() || DEFAULT_TERMINATORS.contains(&c))
+    {
+        warn!("Invalid filler word: {word:?}");
+        return Err(CompareError::InvalidFillerWord(word.to_string()));
+    }
+    Ok(())
+}
+
+/// Splits `txt` on `terminators`, yielding the byte range of each raw sentence body alongside
+/// the terminator that ended it.
+fn split_terminated<'a>(txt: &'a str, terminators: &'a [char]) -> Boundaries<'a, &'a [char]> {
+    split_at_boundaries(txt, terminators)
+}
+
+/// Splits `txt` into sentences according to `options`, yielding the byte range of each raw sentence body
+/// alongside the terminator that ended it.
+fn sentence_ranges<'a>(txt: &'a str, options: &'a EncodeOptions) -> SentenceRanges<'a> {
+    SentenceRanges {
+        boundaries: split_at_boundaries(txt, Cow::Borrowed(options)),
+    }
+}
+
+/// The iterator returned by [`sentence_ranges`]. This is the single implementation of the sentence splitting
+/// rules, shared by [`encode_with`] and [`Sentences`].
+#[derive(Debug, Clone)]
+struct SentenceRanges<'a> {
+    boundaries: Boundaries<'a, Cow<'a, EncodeOptions>>,
+}
+
+impl SentenceRanges<'_> {
+    fn options(&self) -> &EncodeOptions {
+        &self.boundaries.rule
+    }
+}
+
+impl Iterator for SentenceRanges<'_> {
+    type Item = (Range<usize>, Option<char>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let txt = self.boundaries.txt;
+        loop {
+            let (range, terminator) = self.boundaries.next()?;
+            let options = self.options();
+            if !(options.collapse_terminator_runs
+                && range.is_empty()
+                && txt[..range.start].ends_with(options.terminators.as_slice()))
+            {
+                return Some((range, terminator));
+            }
+        }
+    }
+}
+
+/// Decides where [`split_at_boundaries`] ends a sentence.
+trait BoundaryRule {
+    /// Returns `true` if the character `c` at byte `index` of `txt` ends a sentence.
+    fn is_boundary(&self, txt: &str, index: usize, c: char) -> bool;
+}
+
+impl BoundaryRule for &[char] {
+    fn is_boundary(&self, _: &str, _: usize, c: char) -> bool {
+        self.contains(&c)
+    }
+}
+
+impl BoundaryRule for Cow<'_, EncodeOptions> {
+    fn is_boundary(&self, txt: &str, index: usize, c: char) -> bool {
+        (self.terminators.contains(&c)
+            && !(c == '.'
+                && ((self.keep_decimal_numbers && is_decimal_point(txt, index))
+                    || follows_abbreviation(txt, index, &self.abbreviations))))
+            || (self.treat_newlines_as_delimiters
+                && (c == '\n' || (c == '\r' && !txt[index + 1..].starts_with('\n'))))
+    }
+}
+
+/// Counts the words of `sentence` according to `options`.
+fn count_words(sentence: &str, options: &EncodeOptions) -> usize {
+    let is_separator: fn(char) -> bool = if options.split_hyphens {
+        |c| c.is_whitespace() || c == '-'
+    } else {
+        char::is_whitespace
+    };
+
+    let words = sentence
+        .split(is_separator)
+        .filter(|word| !word.is_empty() && is_word(word, options.word_policy));
+
+    match options.word_counting {
+        WordCounting::Whitespace => words.count(),
+        WordCounting::Unicode => words.map(count_unicode_words).sum(),
+    }
+}
+
+/// Returns whether a whitespace-separated token counts as a word under `policy`.
+fn is_word(token: &str, policy: WordPolicy) -> bool {
+    match policy {
+        WordPolicy::All => true,
+        WordPolicy::SkipPunctuation => token.chars().any(char::is_alphanumeric),
+        WordPolicy::Alphabetic => token.chars().any(char::is_alphabetic),
+    }
+}
+
+/// Returns the words of `sentence` under the default [`WordPolicy`], as [`encode`] counts them.
+fn words_of(sentence: &str) -> impl Iterator<Item = &str> {
+    sentence
+        .split_whitespace()
+        .filter(|token| is_word(token, WordPolicy::default()))
+}
+
+/// Counts the words of a whitespace-separated token for `WordCounting::Unicode`: every Han, Hiragana or
+/// Katakana character is a word of its own, and so is every run of other letters and digits, which may contain
+/// apostrophes.
+fn count_unicode_words(token: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in token.chars() {
+        if is_unspaced_script(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            count += usize::from(!in_word);
+            in_word = true;
+        } else if !(in_word && (c == '\'' || c == '\u{2019}')) {
+            in_word = false;
+        }
+    }
+    count
+}
+
+/// Returns `true` for characters of scripts written without spaces between words, which
+/// `WordCounting::Unicode` counts one word per character.
+const fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // Hiragana and Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Unified Ideographs Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{20000}'..='\u{3134F}' // CJK Unified Ideographs Extensions B to G
+    )
+}
+
+/// Splits `txt` at every character where `rule` finds a boundary, yielding the byte range of each raw sentence
+/// body alongside the boundary character that ended it. Like `str::split_inclusive`, no empty sentence is
+/// yielded after a final boundary.
+fn split_at_boundaries<R: BoundaryRule>(txt: &str, rule: R) -> Boundaries<'_, R> {
+    Boundaries {
+        txt,
+        chars: txt.char_indices(),
+        start: 0,
+        done: false,
+        rule,
+    }
+}
+
+/// The iterator returned by [`split_at_boundaries`].
+#[derive(Debug, Clone)]
+struct Boundaries<'a, R> {
+    txt: &'a str,
+    chars: CharIndices<'a>,
+    start: usize,
+    done: bool,
+    rule: R,
+}
+
+impl<R: BoundaryRule> Iterator for Boundaries<'_, R> {
+    type Item = (Range<usize>, Option<char>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        for (i, c) in self.chars.by_ref() {
+            if self.rule.is_boundary(self.txt, i, c) {
+                let sentence = self.start..i;
+                self.start = i + c.len_utf8();
+                return Some((sentence, Some(c)));
+            }
+        }
+        self.done = true;
+        (self.start < self.txt.len()).then_some((self.start..self.txt.len(), None))
+    }
+}
+
+/// Returns `true` if the character at byte `index` of `txt` is flanked by ASCII digits on both sides.
+fn is_decimal_point(txt: &str, index: usize) -> bool {
+    let bytes = txt.as_bytes();
+    index > 0
+        && bytes[index - 1].is_ascii_digit()
+        && bytes.get(index + 1).is_some_and(u8::is_ascii_digit)
+}
+
+/// Returns `true` if the period at byte `index` of `txt` directly follows one of `abbreviations`, ignoring case.
+fn follows_abbreviation<S: AsRef<str>>(txt: &str, index: usize, abbreviations: &[S]) -> bool {
+    let before = &txt[..index];
+    let word_start = before
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| !c.is_alphanumeric())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let word = &before[word_start..];
+    !word.is_empty()
+        && abbreviations.iter().any(|a| {
+            a.as_ref()
+                .chars()
+                .map(fold_case)
+                .eq(word.chars().map(fold_case))
+        })
+}
+
+/// Returns the prefix of `sentence` that ends with its `keep`-th word, skipping tokens that are not words under
+/// the default [`WordPolicy`].
+fn truncate_words(sentence: &str, keep: usize) -> &str {
+    let mut words = 0;
+    let mut start = None;
+    for (i, c) in sentence.char_indices().chain([(sentence.len(), ' ')]) {
+        if c.is_whitespace() {
+            if let Some(token) = start.take().map(|start| &sentence[start..i]) {
+                words += usize::from(is_word(token, WordPolicy::default()));
+                if words == keep {
+                    return &sentence[..i];
+                }
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    sentence
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_encode_valid_input() {
-        let input = "This is a sentence. This is another.";
-        let result = encode(input).expect("Failed to encode");
-        assert_eq!(result, vec![4, 3]);
+    fn test_encode_valid_input() {
+        let input = "This is a sentence. This is another.";
+        let result = encode(input).expect("Failed to encode");
+        assert_eq!(result, vec![4, 3]);
+    }
+
+    #[test]
+    fn test_encode_non_ascii_input() {
+        let input = "This is a sentence with non-ascii char ö.";
+        match encode(input) {
+            Ok(encoded) => println!("Encoded text: {encoded:?}"),
+            Err(EncodingError::NonAsciiInput) => println!("Input text must be ASCII"),
+            Err(EncodingError::NoValidSentences) => println!("No valid sentences found"),
+            Err(EncodingError::EmptyInput) => println!("Input text is empty"),
+            #[cfg(feature = "std")]
+            Err(EncodingError::Io(kind)) => println!("Failed to read input: {kind}"),
+        }
+    }
+
+    #[test]
+    fn test_decode_basic() {
+        let encoded = vec![1, 26, 5]; // Corresponding to some encoded numbers
+        let character_set = "abcdefghijklmnopqrstuvwxyz";
+        let result = decode(&encoded, character_set).expect("Failed to decode");
+        assert_eq!(result, "aze"); // Assuming this is the expected decoded string
+    }
+
+    #[test]
+    fn test_decode_with_empty_input() {
+        let encoded = vec![];
+        let character_set = "abcdefghijklmnopqrstuvwxyz";
+        let result = decode(&encoded, character_set).expect("Failed to decode");
+        assert_eq!(result, ""); // Decoding an empty vector should result in an empty string
+    }
+
+    #[test]
+    fn test_basic_wps_encode() {
+        let input = "Hello, this is a test.\n        Does this work?\n        I sure hope so.";
+        let expected = vec![5, 3, 4];
+        let result = encode(input).expect("Failed to encode");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_incomplete_sentence_encode() {
+        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
+        let expected = vec![1, 5, 3];
+        let result = encode(input).expect("Failed to encode");
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_normal_decode() {
+        let hidden_msg = "SECRET";
+        let cover_text = "\n        Hello Bob, I hope you are well and good, I would like to know if you are free tomorrow.\n        Hmm, How about a picnic?\n\n        At the park?\n\n        I would very much look forward to that, but will jane bring her dog, i was just wondering?\n\n        Anyway i would like to.\n        But how many days until Sara will be making her famous lemon drizzle cake, it was to die for, before.\n        ";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let encoded = encode(cover_text).unwrap(); // Note that the 'expect' method could be used here as well.
+        let result = decode(&encoded, character_set).expect("Failed to decode");
+        assert_eq!(result, hidden_msg);
+    }
+
+    #[test]
+    fn test_extract() {
+        let hidden_msg = "SECRET";
+        let cover_text = "\n        Hello Bob, I hope you are well and good, I would like to know if you are free tomorrow.\n        Hmm, How about a picnic?\n\n        At the park?\n\n        I would very much look forward to that, but will jane bring her dog, i was just wondering?\n\n        Anyway i would like to.\n        But how many days until Sara will be making her famous lemon drizzle cake, it was to die for, before.\n        ";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = extract(cover_text, character_set).expect("Failed to extract");
+        assert_eq!(result, hidden_msg);
+        assert_eq!(
+            extract("", character_set),
+            Err(ExtractError::EncodingError(EncodingError::EmptyInput))
+        );
+        assert_eq!(
+            extract(cover_text, ""),
+            Err(ExtractError::DecodingError(
+                DecodingError::EmptyCharacterSet
+            ))
+        );
+    }
+
+    #[test]
+    fn test_compare_exact_match() {
+        let secret_message = "HELLO";
+        let cover_text = "This is a sentence. And another one.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = compare(secret_message, cover_text, character_set).expect("Failed to compare");
+        assert_eq!(result, vec![4, 2, 12, 12, 15]);
+    }
+
+    #[test]
+    fn test_compare_length_mismatch() {
+        let secret_message = "HELLO";
+        let cover_text = "This is a sentence. This is another. And yet another.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = compare(secret_message, cover_text, character_set).expect("Failed to compare");
+        assert_eq!(result, vec![4, 2, 9, 12, 15]);
+    }
+
+    #[test]
+    fn test_compare_non_ascii_input() {
+        let secret_message = "HELLO";
+        let cover_text = "This sentence contains ö.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = compare(secret_message, cover_text, character_set);
+        assert_eq!(
+            result,
+            Err(CompareError::EncodingError(EncodingError::NonAsciiInput))
+        );
+    }
+
+    #[test]
+    fn test_compare_error_conversion_and_source() {
+        fn encode_cover(cover_text: &str) -> Result<Vec<usize>, CompareError> {
+            Ok(encode(cover_text)?)
+        }
+
+        let error = encode_cover("Not ascii ö.").unwrap_err();
+        assert_eq!(
+            error,
+            CompareError::EncodingError(EncodingError::NonAsciiInput)
+        );
+        assert_eq!(
+            core::error::Error::source(&error).map(ToString::to_string),
+            Some(EncodingError::NonAsciiInput.to_string())
+        );
+        assert!(core::error::Error::source(&CompareError::CharacterNotFound('!')).is_none());
+    }
+
+    #[test]
+    fn test_changes_for_huge_word_count_is_an_error() {
+        // A cover sentence of `usize::MAX` words cannot be built in memory, so feed such a count straight into
+        // the change computation that `compare` uses. No change to or from it fits an `isize`.
+        let huge = usize::MAX;
+        assert_eq!(
+            changes_for_targets(&[1], &[huge]),
+            Err(CompareError::ValueOutOfRange)
+        );
+        assert_eq!(
+            changes_for_targets(&[1], &[1, huge]),
+            Err(CompareError::ValueOutOfRange)
+        );
+        assert_eq!(changes_for_targets(&[1], &[3, 2]), Ok(vec![-2, -2]));
+    }
+
+    #[test]
+    fn test_compare_character_not_found() {
+        let secret_message = "HELLO!";
+        let cover_text = "This is a sentence. And another one.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = compare(secret_message, cover_text, character_set);
+        assert_eq!(result, Err(CompareError::CharacterNotFound('!')));
+    }
+
+    #[test]
+    fn test_compare_cover_without_sentences() {
+        let secret_message = "HI";
+        let cover_text = "... !!!";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = compare(secret_message, cover_text, character_set);
+        assert_eq!(
+            result,
+            Err(CompareError::EncodingError(EncodingError::NoValidSentences))
+        );
+    }
+
+    #[test]
+    fn test_compare_empty_input() {
+        let secret_message = "";
+        let cover_text = "";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = compare(secret_message, cover_text, character_set).expect("Failed to compare");
+        assert_eq!(result, Vec::<isize>::new());
+    }
+
+    #[test]
+    fn test_encode_with_semicolon_terminators() {
+        let input = "This is a clause; here is another one; and a third";
+        let result = encode_with_terminators(input, &[';']).expect("Failed to encode");
+        assert_eq!(result, vec![4, 4, 3]);
+    }
+
+    #[test]
+    fn test_encode_with_terminators_ignores_default_set() {
+        let input = "Hello world. Still the same clause; new clause.";
+        let result = encode_with_terminators(input, &[';']).expect("Failed to encode");
+        assert_eq!(result, vec![6, 2]);
+    }
+
+    #[test]
+    fn test_encode_with_custom_delimiters() {
+        assert_eq!(
+            encode_with_terminators("a b; c d e;", &[';']),
+            Ok(vec![2, 3])
+        );
+        assert_eq!(
+            encode_with_delimiters("a b; c d e;", &[';']),
+            Ok(vec![2, 3])
+        );
+        assert_eq!(
+            encode_with_terminators("a b c।", &['।']),
+            Err(EncodingError::NonAsciiInput)
+        );
+        let options = EncodeOptions {
+            terminators: vec!['।'],
+            allow_non_ascii: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with("राम घर गया। वह सो गया।", &options),
+            Ok(vec![3, 3])
+        );
+    }
+
+    #[test]
+    fn test_encode_with_empty_terminators() {
+        let input = "No terminators. Whole text is one sentence.";
+        let result = encode_with_terminators(input, &[]).expect("Failed to encode");
+        assert_eq!(result, vec![7]);
+    }
+
+    #[test]
+    fn test_apply_changes_round_trip() {
+        let secret_message = "SECRET";
+        let cover_text =
+            "Hello Bob. How are you doing today? I hope the weather is nice! Let us meet.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let changes =
+            compare(secret_message, cover_text, character_set).expect("Failed to compare");
+        let stego_text = apply_changes(cover_text, &changes, "indeed").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        let decoded = decode(&encoded, character_set).expect("Failed to decode");
+        assert_eq!(decoded, secret_message);
+    }
+
+    #[test]
+    fn test_apply_changes_preserves_terminators() {
+        let cover_text = "One two three! Four five? Six.";
+        let result = apply_changes(cover_text, &[-1, 1, 0], "x").expect("Failed to apply");
+        assert_eq!(result, "One two! Four five x? Six.");
+    }
+
+    #[test]
+    fn test_apply_changes_drops_emptied_sentences() {
+        let cover_text = "Keep me. Drop this sentence. Keep.";
+        let result = apply_changes(cover_text, &[0, -3, 0], "x").expect("Failed to apply");
+        assert_eq!(encode(&result).expect("Failed to encode"), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_apply_changes_appends_new_sentences() {
+        let cover_text = "One two. Unterminated fragment";
+        let result = apply_changes(cover_text, &[0, 0, 3], "x").expect("Failed to apply");
+        assert_eq!(result, "One two. Unterminated fragment. x x x.");
+    }
+
+    #[test]
+    fn test_apply_changes_insufficient_words() {
+        let cover_text = "One two. Three.";
+        let result = apply_changes(cover_text, &[0, -2], "x");
+        assert_eq!(
+            result,
+            Err(CompareError::InsufficientWords {
+                sentence: 1,
+                available: 1,
+                requested: 2,
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Sentence 2 has 1 words, cannot remove 2"
+        );
+        assert_eq!(
+            CompareError::SentenceTooLong {
+                sentence: 0,
+                words: 15,
+                max: 12
+            }
+            .to_string(),
+            "Sentence 1 needs 15 words, more than the maximum of 12"
+        );
+    }
+
+    #[test]
+    fn test_apply_changes_invalid_filler_word() {
+        let result = apply_changes("One two.", &[1], "two words");
+        assert_eq!(
+            result,
+            Err(CompareError::InvalidFillerWord("two words".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_encode_spans_mixed_terminators() {
+        let input = "Hello there! How are you? I am fine. Thanks";
+        let result = encode_spans(input).expect("Failed to encode");
+        assert_eq!(result, vec![(2, '!'), (3, '?'), (3, '.'), (1, '.')]);
+    }
+
+    #[test]
+    fn test_encode_spans_matches_encode() {
+        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
+        let counts: Vec<usize> = encode_spans(input)
+            .expect("Failed to encode")
+            .into_iter()
+            .map(|(count, _)| count)
+            .collect();
+        assert_eq!(counts, encode(input).expect("Failed to encode"));
+    }
+
+    #[test]
+    fn test_compare_case_insensitive_lowercase_secret() {
+        let cover_text = "This is a sentence. And another one.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(
+            compare("hello", cover_text, character_set),
+            Err(CompareError::CharacterNotFound('h'))
+        );
+        let result = compare_case_insensitive("hello", cover_text, character_set)
+            .expect("Failed to compare");
+        assert_eq!(result, vec![4, 2, 12, 12, 15]);
+    }
+
+    #[test]
+    fn test_compare_case_insensitive_last_occurrence_wins() {
+        let cover_text = "One. One.";
+        let result = compare_case_insensitive("Aa", cover_text, "aAbB").expect("Failed to compare");
+        assert_eq!(result, vec![1, 1]);
+
+        let options = CompareOptions {
+            case_insensitive: true,
+            ..CompareOptions::default()
+        };
+        let expected = compare("A", cover_text, "aBA");
+        assert_eq!(expected, Ok(vec![2, -1]));
+        assert_eq!(compare_case_insensitive("A", cover_text, "aBA"), expected);
+        assert_eq!(
+            compare_with_options("A", cover_text, "aBA", &options),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_decode_case_insensitive() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let changes = compare_case_insensitive("hey", "A b. C d. E f.", character_set)
+            .expect("Failed to compare");
+        let stego_text = apply_changes("A b. C d. E f.", &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        let result = decode_case_insensitive(&encoded, character_set).expect("Failed to decode");
+        assert_eq!(result, "hey");
+    }
+
+    #[test]
+    fn test_encode_with_ranges_slices_sentences() {
+        let input = "  Hello, this is a test.\n Does this work?! I sure hope so";
+        let result = encode_with_ranges(input).expect("Failed to encode");
+        let sentences: Vec<&str> = result.iter().map(|(r, _)| &input[r.clone()]).collect();
+        assert_eq!(
+            sentences,
+            vec!["Hello, this is a test", "Does this work", "I sure hope so"]
+        );
+        let counts: Vec<usize> = result.into_iter().map(|(_, count)| count).collect();
+        assert_eq!(counts, encode(input).expect("Failed to encode"));
+    }
+
+    #[test]
+    fn test_encode_unicode_accented_words() {
+        let input = "café résumé.";
+        assert_eq!(encode(input), Err(EncodingError::NonAsciiInput));
+        let result = encode_unicode(input).expect("Failed to encode");
+        assert_eq!(result, vec![2]);
+    }
+
+    #[test]
+    fn test_encode_unicode_combining_characters() {
+        // "cafe\u{301}" uses a combining acute accent rather than a precomposed 'é'.
+        let input = "Un cafe\u{301} noir. Re\u{301}sume\u{301} fini!";
+        let result = encode_unicode(input).expect("Failed to encode");
+        assert_eq!(result, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_encode_unicode_matches_encode_on_ascii() {
+        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
+        assert_eq!(encode_unicode(input), encode(input));
+    }
+
+    #[test]
+    fn test_decode_strict_rejects_out_of_range_codes() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(
+            decode_strict(&[8, 9], character_set).expect("Failed to decode"),
+            "HI"
+        );
+        assert!(matches!(
+            decode_strict(&[8, 35], character_set),
+            Err(DecodingError::InvalidCode(35))
+        ));
+        assert_eq!(
+            decode(&[8, 35], character_set).expect("Failed to decode"),
+            "HI"
+        );
+    }
+
+    #[test]
+    fn test_decode_strict_code_27_boundary() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(decode_strict(&[26], character_set), Ok("Z".to_string()));
+        assert_eq!(
+            decode_strict(&[27], character_set),
+            Err(DecodingError::InvalidCode(27))
+        );
+        assert_eq!(decode(&[27], character_set), Ok("A".to_string()));
+        assert_eq!(decode_strict(&[3], "αβγ"), Ok("γ".to_string()));
+        assert_eq!(
+            decode_strict(&[4], "αβγ"),
+            Err(DecodingError::InvalidCode(4))
+        );
+    }
+
+    #[test]
+    fn test_decode_strict_empty_character_set() {
+        assert!(matches!(
+            decode_strict(&[1], ""),
+            Err(DecodingError::EmptyCharacterSet)
+        ));
+    }
+
+    #[test]
+    fn test_capacity() {
+        assert_eq!(capacity("One. Two. Three.").expect("Failed to encode"), 3);
+        let cover_text = "Hello, this is a test.\n Does this work?! I sure hope so";
+        assert_eq!(
+            capacity(cover_text),
+            encode(cover_text).map(|encoded| encoded.len())
+        );
+        assert_eq!(
+            capacity("Hello. This is a great tool. .. Bad sentence punctuation")
+                .expect("Failed to encode"),
+            3
+        );
+    }
+
+    #[test]
+    fn test_capacity_no_valid_sentences() {
+        assert_eq!(capacity("... !!!"), Err(EncodingError::NoValidSentences));
+    }
+
+    #[test]
+    fn test_generate_cover_round_trip() {
+        let secret_message = "HELLO";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = generate_cover(secret_message, character_set, &["the", "quick", "fox"])
+            .expect("Failed to generate");
+        let encoded = encode(&cover_text).expect("Failed to encode");
+        assert_eq!(encoded, vec![8, 5, 12, 12, 15]);
+        let decoded = decode(&encoded, character_set).expect("Failed to decode");
+        assert_eq!(decoded, secret_message);
+    }
+
+    #[test]
+    fn test_generate_cover_errors() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(
+            generate_cover("HI", character_set, &[]),
+            Err(CompareError::EmptyWordPool)
+        );
+        assert_eq!(
+            generate_cover("HI", character_set, &["ok", "end."]),
+            Err(CompareError::InvalidFillerWord("end.".to_string()))
+        );
+        assert_eq!(
+            generate_cover("hi", character_set, &["word"]),
+            Err(CompareError::CharacterNotFound('h'))
+        );
+    }
+
+    #[test]
+    fn test_encode_wrapped_decode() {
+        let input = "One two three four five six seven eight. One two three four five six seven eight nine.";
+        let encoded = encode_wrapped(input).expect("Failed to encode");
+        assert_eq!(encoded, Encoded(encode(input).expect("Failed to encode")));
+        assert_eq!(
+            encoded.decode("ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+            Ok("HI".to_string())
+        );
+        assert_eq!(encoded.decode(""), Err(DecodingError::EmptyCharacterSet));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_encoded_json_round_trip() {
+        let encoded = encode_wrapped("Hello world. How are you?").expect("Failed to encode");
+        let json = serde_json::to_string(&encoded).expect("Failed to serialize");
+        assert_eq!(json, "[2,3]");
+        let decoded: Encoded = serde_json::from_str(&json).expect("Failed to deserialize");
+        assert_eq!(decoded, encoded);
+    }
+
+    #[test]
+    fn test_encoded_conversions() {
+        let encoded: Encoded = encode("Hello world. How are you?")
+            .expect("Failed to encode")
+            .into();
+        assert_eq!(encoded.as_ref(), &[2, 3]);
+        let decoded = decode(encoded.as_ref(), "ABC").expect("Failed to decode");
+        assert_eq!(decoded, "BC");
+        assert_eq!(Vec::from(encoded), vec![2, 3]);
+    }
+
+    /// A reader that hands out a single byte per call, to exercise chunk boundaries.
+    #[cfg(feature = "std")]
+    struct ByteReader<'a>(&'a [u8]);
+
+    #[cfg(feature = "std")]
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((&byte, rest)) if !buf.is_empty() => {
+                    buf[0] = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_encode_reader_matches_encode() {
+        let input = "Hello, this is a test.\n        Does this work?\n        I sure hope so";
+        let result = encode_reader(input.as_bytes()).expect("Failed to encode");
+        assert_eq!(result, encode(input).expect("Failed to encode"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_encode_reader_one_byte_at_a_time() {
+        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
+        let result = encode_reader(ByteReader(input.as_bytes())).expect("Failed to encode");
+        assert_eq!(result, vec![1, 5, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_encode_reader_errors() {
+        assert_eq!(
+            encode_reader("Non-ascii ö.".as_bytes()),
+            Err(EncodingError::NonAsciiInput)
+        );
+        assert_eq!(
+            encode_reader(b"... !!!".as_slice()),
+            Err(EncodingError::NoValidSentences)
+        );
+    }
+
+    #[test]
+    fn test_encode_iter_matches_encode() {
+        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
+        let result: Vec<usize> = encode_iter(input).collect();
+        assert_eq!(result, encode(input).expect("Failed to encode"));
+    }
+
+    #[test]
+    fn test_encode_iter_no_valid_sentences() {
+        assert_eq!(encode_iter("... !!!").count(), 0);
+    }
+
+    #[test]
+    fn test_decode_with_offset() {
+        let character_set = "abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(
+            decode_with_offset(&[1, 26, 5], character_set, 1).expect("Failed to decode"),
+            decode(&[1, 26, 5], character_set).expect("Failed to decode")
+        );
+        assert_eq!(
+            decode_with_offset(&[0, 25, 4], character_set, 0).expect("Failed to decode"),
+            "aze"
+        );
+        assert_eq!(
+            decode_with_offset(&[26], character_set, 0).expect("Failed to decode"),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_decode_with_offset_shift() {
+        let encoded = [1, 2, 3];
+        assert_eq!(
+            decode_with_offset(&encoded, "ABCDE", 0),
+            Ok("BCD".to_string())
+        );
+        assert_eq!(
+            decode_with_offset(&encoded, "ABCDE", 1),
+            Ok("ABC".to_string())
+        );
+        // Unlike `decode`, a zero code is not skipped, so offset 1 rejects it.
+        assert_eq!(decode(&[0, 1], "ABCDE"), Ok("A".to_string()));
+        assert_eq!(
+            decode_with_offset(&[0, 1], "ABCDE", 1),
+            Err(DecodingError::InvalidCode(0))
+        );
+    }
+
+    #[test]
+    fn test_decode_with_offset_underflow() {
+        assert!(matches!(
+            decode_with_offset(&[5, 2], "ABCDE", 3),
+            Err(DecodingError::InvalidCode(2))
+        ));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_compare_parallel_matches_compare() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "This is a sentence. This is another. And yet another. One. Two words.";
+        for secret_message in ["", "HI", "HELLO", "HELLOWORLD"] {
+            assert_eq!(
+                compare_parallel(secret_message, cover_text, character_set),
+                compare(secret_message, cover_text, character_set)
+            );
+        }
+        assert_eq!(
+            compare_parallel("HELLO", "Non-ascii ö.", character_set),
+            Err(CompareError::EncodingError(EncodingError::NonAsciiInput))
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_encode_parallel_matches_encode() {
+        let large =
+            "This is a sentence. Another one here! Is it? Dr. Smith paid 3.14 dollars.".repeat(500);
+        for text in ["One.", "No terminator", "A b. . c d e?", large.as_str()] {
+            assert_eq!(encode_parallel(text), encode(text));
+        }
+        assert_eq!(encode_parallel("..."), Err(EncodingError::NoValidSentences));
+        assert_eq!(
+            encode_parallel("Non-ascii ö."),
+            Err(EncodingError::NonAsciiInput)
+        );
+    }
+
+    #[test]
+    fn test_decode_wraps_around_character_set() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let result = decode(&[27, 52, 53, 0, 2], character_set).expect("Failed to decode");
+        assert_eq!(result, "AZAB");
+    }
+
+    #[test]
+    fn test_encode_with_abbreviations() {
+        let input = "Dr. Jones is here.";
+        let result =
+            encode_with_abbreviations(input, DEFAULT_ABBREVIATIONS).expect("Failed to encode");
+        assert_eq!(result, vec![4]);
+    }
+
+    #[test]
+    fn test_encode_with_abbreviations_only_matches_whole_words() {
+        let input = "Mrs. Dr. Smith met Mr. Brown. It was Sunday. Strange, Mr.";
+        let result =
+            encode_with_abbreviations(input, DEFAULT_ABBREVIATIONS).expect("Failed to encode");
+        assert_eq!(result, vec![6, 3, 2]);
+        let result = encode_with_abbreviations("Dst. Here.", &["St"]).expect("Failed to encode");
+        assert_eq!(result, vec![1, 1]);
+    }
+
+    #[test]
+    fn test_encode_with_abbreviations_case_insensitive() {
+        let input = "Dr. Smith went home. He slept.";
+        assert_eq!(encode(input), Ok(vec![1, 3, 2]));
+        assert_eq!(
+            encode_with_abbreviations(input, &["Dr", "Mr"]),
+            Ok(vec![4, 2])
+        );
+        assert_eq!(
+            encode_with_abbreviations("DR. Smith and mr. Jones left. Bye Mr.", &["Dr", "Mr"]),
+            Ok(vec![6, 2])
+        );
+        assert_eq!(encode_with_abbreviations("Mr.", &["Dr", "Mr"]), Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_encode_with_keep_decimal_numbers() {
+        let input = "Pi is 3.14 and e is 2.71 roughly.";
+        assert_eq!(encode(input).expect("Failed to encode"), vec![3, 5, 2]);
+        let options = EncodeOptions {
+            keep_decimal_numbers: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with(input, &options).expect("Failed to encode"),
+            vec![8]
+        );
+    }
+
+    #[test]
+    fn test_encode_with_keep_decimal_numbers_sentence_ending_in_digit() {
+        let input = "The answer is 42. 7 is next. Version 1.2.3 shipped.";
+        let options = EncodeOptions {
+            keep_decimal_numbers: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with(input, &options).expect("Failed to encode"),
+            vec![4, 3, 3]
+        );
+    }
+
+    #[test]
+    fn test_encode_with_default_options_matches_encode() {
+        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
+        assert_eq!(encode_with(input, &EncodeOptions::default()), encode(input));
+    }
+
+    #[test]
+    fn test_encode_with_combined_options() {
+        let input = "Dr. Müller paid 2.50 here; St. Paul is near";
+        let options = EncodeOptions {
+            terminators: vec![';'],
+            treat_newlines_as_delimiters: false,
+            allow_non_ascii: true,
+            abbreviations: vec!["Dr".to_string(), "St".to_string()],
+            keep_decimal_numbers: true,
+            split_hyphens: false,
+            word_policy: WordPolicy::All,
+            word_counting: WordCounting::Whitespace,
+            collapse_terminator_runs: false,
+            keep_empty_sentences: false,
+            allow_unicode_whitespace: false,
+            include_unterminated: true,
+        };
+        assert_eq!(
+            encode_with(input, &options).expect("Failed to encode"),
+            vec![5, 4]
+        );
+        let options = EncodeOptions {
+            allow_non_ascii: true,
+            abbreviations: vec!["Dr".to_string(), "St".to_string()],
+            keep_decimal_numbers: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with(input, &options).expect("Failed to encode"),
+            vec![9]
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text =
+            generate_cover("SECRET", character_set, &["word"]).expect("Failed to generate");
+        assert_eq!(verify("SECRET", &cover_text, character_set), Ok(true));
+        assert_eq!(verify("SECRECY", &cover_text, character_set), Ok(false));
+        assert_eq!(verify("SEC", &cover_text, character_set), Ok(true));
+    }
+
+    #[test]
+    fn test_verify_errors() {
+        assert_eq!(
+            verify("HI", "Non-ascii ö.", "ABC"),
+            Err(CompareError::EncodingError(EncodingError::NonAsciiInput))
+        );
+        assert_eq!(
+            verify("HI", "One two.", ""),
+            Err(CompareError::DecodingError(
+                DecodingError::EmptyCharacterSet
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_charset() {
+        assert_eq!(validate_charset("ABCDEFGHIJKLMNOPQRSTUVWXYZ"), Ok(()));
+        assert_eq!(validate_charset(""), Err(DecodingError::EmptyCharacterSet));
+        assert_eq!(
+            validate_charset("ABCDEFGHIJKLMNOPQRSTUVWXYZA"),
+            Err(DecodingError::DuplicateCharacter('A'))
+        );
+        assert_eq!(validate_charset("aA"), Ok(()));
+    }
+
+    #[test]
+    fn test_decode_strict_rejects_duplicate_characters() {
+        assert_eq!(
+            decode_strict(&[1, 2], "ABB"),
+            Err(DecodingError::DuplicateCharacter('B'))
+        );
+        assert_eq!(decode(&[1, 2], "ABB"), Ok("AB".to_string()));
+    }
+
+    #[test]
+    fn test_encode_line_oriented() {
+        let input = "first line here\nsecond line\nand the third line";
+        assert_eq!(encode(input).expect("Failed to encode"), vec![9]);
+        assert_eq!(
+            encode_line_oriented(input).expect("Failed to encode"),
+            vec![3, 2, 4]
+        );
+    }
+
+    #[test]
+    fn test_encode_line_oriented_skips_blank_lines() {
+        let input = "\nfirst line. Still first\n   \n\r\nlast line\r\n";
+        assert_eq!(
+            encode_line_oriented(input).expect("Failed to encode"),
+            vec![2, 2, 2]
+        );
+    }
+
+    #[test]
+    fn test_decode_with_zero_policy() {
+        let encoded = vec![8, 0, 9];
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(
+            decode_with_zero_policy(&encoded, character_set, ZeroPolicy::Skip),
+            Ok("HI".to_string())
+        );
+        assert_eq!(
+            decode_with_zero_policy(&encoded, character_set, ZeroPolicy::Skip),
+            decode(&encoded, character_set)
+        );
+        assert_eq!(
+            decode_with_zero_policy(&encoded, character_set, ZeroPolicy::Error),
+            Err(DecodingError::InvalidCode(0))
+        );
+    }
+
+    #[test]
+    fn test_encode_detailed() {
+        let input = "Hello, this is a test.\n        Does this work?\n        I sure hope so.";
+        let result = encode_detailed(input).expect("Failed to encode");
+        assert_eq!(
+            result[0],
+            SentenceInfo {
+                word_count: 5,
+                text: "Hello, this is a test".to_string(),
+                words: vec!["Hello,", "this", "is", "a", "test"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            }
+        );
+        let counts: Vec<usize> = result.iter().map(|info| info.word_count).collect();
+        assert_eq!(counts, encode(input).expect("Failed to encode"));
+    }
+
+    #[test]
+    fn test_packed_round_trip() {
+        let secret_message = "HELLOWORLD";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "One. Two. Three.";
+        let changes = compare_packed(secret_message, cover_text, character_set, 2)
+            .expect("Failed to compare");
+        assert_eq!(changes.len(), 5);
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        let decoded = decode_packed(&encoded, character_set, 2).expect("Failed to decode");
+        assert_eq!(decoded, secret_message);
+    }
+
+    #[test]
+    fn test_packed_pads_last_chunk() {
+        let changes = compare_packed("CAB", "One.", "ABC", 2).expect("Failed to compare");
+        // "CA" = 2 * 3 + 0 + 1 = 7 and "BA" (padded) = 1 * 3 + 0 + 1 = 4.
+        assert_eq!(changes, vec![6, 4]);
+        assert_eq!(
+            decode_packed(&[7, 4], "ABC", 2).expect("Failed to decode"),
+            "CABA"
+        );
+    }
+
+    #[test]
+    fn test_decode_packed_single_char_matches_decode_strict() {
+        let encoded = vec![1, 26, 5];
+        let character_set = "abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(
+            decode_packed(&encoded, character_set, 1),
+            decode_strict(&encoded, character_set)
+        );
+    }
+
+    #[test]
+    fn test_packed_errors() {
+        assert_eq!(
+            decode_packed(&[1], "ABC", 0),
+            Err(DecodingError::ZeroCharsPerSentence)
+        );
+        assert_eq!(
+            decode_packed(&[10], "ABC", 2),
+            Err(DecodingError::InvalidCode(10))
+        );
+        assert_eq!(
+            decode_packed(&[1], "ABC", 1 << 40),
+            Err(DecodingError::CharsPerSentenceTooLarge)
+        );
+        assert_eq!(
+            decode_packed(&[1, 2], "ABC", usize::MAX),
+            Err(DecodingError::CharsPerSentenceTooLarge)
+        );
+        assert_eq!(
+            compare_packed("AB", "One.", "ABC", 1 << 40),
+            Err(CompareError::DecodingError(
+                DecodingError::CharsPerSentenceTooLarge
+            ))
+        );
+        assert_eq!(
+            compare_packed("AB", "One.", "ABC", 0),
+            Err(CompareError::DecodingError(
+                DecodingError::ZeroCharsPerSentence
+            ))
+        );
+        assert_eq!(
+            compare_packed("AZ", "One.", "ABC", 2),
+            Err(CompareError::CharacterNotFound('Z'))
+        );
+    }
+
+    #[test]
+    fn test_compare_strict() {
+        let cover_text = "This is a sentence. This is another. And yet another.";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        assert_eq!(
+            compare_strict("HELLO", cover_text, character_set),
+            Err(CompareError::InsufficientCapacity { have: 3, need: 5 })
+        );
+        assert_eq!(
+            compare_strict("HEL", cover_text, character_set),
+            compare("HEL", cover_text, character_set)
+        );
+        assert_eq!(
+            compare_strict("HI", cover_text, character_set),
+            Ok(vec![4, 6, -3])
+        );
+    }
+
+    #[test]
+    fn test_encode_with_split_hyphens() {
+        let input = "a well-known fact.";
+        assert_eq!(
+            encode_with(input, &EncodeOptions::default()).expect("Failed to encode"),
+            vec![3]
+        );
+        let options = EncodeOptions {
+            split_hyphens: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with(input, &options).expect("Failed to encode"),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn test_encode_with_split_hyphens_ignores_bare_dashes() {
+        let options = EncodeOptions {
+            split_hyphens: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with("state-of-the-art -- truly. - -", &options).expect("Failed to encode"),
+            vec![5]
+        );
+    }
+
+    #[test]
+    fn test_charset_index() {
+        let index = CharsetIndex::new("ABCDEFGHIJKLMNOPQRSTUVWXYZ").expect("Invalid charset");
+        assert_eq!(index.len(), 26);
+        assert!(!index.is_empty());
+        for code in 1..=26 {
+            let c = index.char_at(code).expect("Missing character");
+            assert_eq!(index.index_of(c), Some(code));
+        }
+        assert_eq!(index.char_at(0), None);
+        assert_eq!(index.char_at(27), None);
+        assert_eq!(index.index_of('a'), None);
+        assert_eq!(CharsetIndex::new(""), Err(DecodingError::EmptyCharacterSet));
+        assert_eq!(
+            CharsetIndex::new("ABA"),
+            Err(DecodingError::DuplicateCharacter('A'))
+        );
+    }
+
+    #[test]
+    fn test_compare_and_decode_with_index() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let index = CharsetIndex::new(character_set).expect("Invalid charset");
+        let cover_text = "This is a sentence. This is another. And yet another.";
+        for secret_message in ["", "HI", "HELLO"] {
+            assert_eq!(
+                compare_with_index(secret_message, cover_text, &index),
+                compare(secret_message, cover_text, character_set)
+            );
+        }
+        let encoded = vec![1, 0, 26, 27, 53];
+        assert_eq!(
+            Ok(decode_with_index(&encoded, &index)),
+            decode(&encoded, character_set)
+        );
+    }
+
+    #[test]
+    fn test_message_to_counts() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "This is a sentence. And another one.";
+        let counts = message_to_counts("HELLO", character_set).expect("Failed to convert");
+        let cover_encoded = encode(cover_text).expect("Failed to encode");
+        let changes: Vec<isize> = counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| {
+                let cover = cover_encoded.get(i).copied().unwrap_or(0);
+                isize::try_from(count).unwrap() - isize::try_from(cover).unwrap()
+            })
+            .collect();
+        assert_eq!(Ok(changes), compare("HELLO", cover_text, character_set));
+        assert_eq!(message_to_counts("", character_set), Ok(vec![]));
+        assert_eq!(
+            message_to_counts("Hi", character_set),
+            Err(CompareError::CharacterNotFound('i'))
+        );
+    }
+
+    #[test]
+    fn test_normalize_crlf_and_tabs() {
+        let lf = "Hello, this is a test.\nDoes this work?\nI sure hope so.";
+        let crlf = "Hello,\tthis is  a test.\r\n\tDoes this\t\twork?\r\nI sure hope so.\r\n";
+        assert_eq!(normalize(lf), normalize(crlf));
+        assert_eq!(encode(crlf), encode(lf));
+        assert_eq!(encode(&normalize(crlf)), encode(lf));
+
+        let details = encode_detailed(crlf).expect("Failed to encode");
+        assert_eq!(details[0].text, "Hello, this is a test");
+        assert_eq!(details[1].text, "Does this work");
+        assert_eq!(normalize(" \t\r\n "), "");
+    }
+
+    #[test]
+    fn test_decode_with_closure() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let encoded = [8, 5, 12, 12, 15];
+        let table = |code: usize| character_set.chars().nth(code.checked_sub(1)?);
+        assert_eq!(
+            decode_with(&encoded, table),
+            decode_strict(&encoded, character_set)
+        );
+        assert_eq!(
+            decode_with(&[8, 0, 5], table),
+            Err(DecodingError::InvalidCode(0))
+        );
+        assert_eq!(decode_with(&[], table), Ok(String::new()));
     }
 
     #[test]
-    fn test_encode_non_ascii_input() {
-        let input = "This is a sentence with non-ascii char ö.";
-        match encode(input) {
-            Ok(encoded) => println!("Encoded text: {encoded:?}"),
-            Err(EncodingError::NonAsciiInput) => println!("Input text must be ASCII"),
-            Err(EncodingError::NoValidSentences) => println!("No valid sentences found"),
-        }
+    fn test_encode_word_policy_alphabetic() {
+        let input = "Wow --- 42 amazing. The 42 --- answers! 42 ---.";
+        assert_eq!(encode(input), Ok(vec![3, 3, 1]));
+
+        let options = EncodeOptions {
+            word_policy: WordPolicy::Alphabetic,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(encode_with(input, &options), Ok(vec![2, 2]));
+        // `!` is a terminator, so each `!` ends a (here empty) sentence rather than being counted as a word.
+        assert_eq!(
+            encode_with("Wow ! ! ! amazing.", &options),
+            encode("Wow ! ! ! amazing.")
+        );
+        assert_eq!(
+            encode_with("! ! !. 42?", &options),
+            Err(EncodingError::NoValidSentences)
+        );
     }
 
     #[test]
-    fn test_decode_basic() {
-        let encoded = vec![1, 26, 5]; // Corresponding to some encoded numbers
-        let character_set = "abcdefghijklmnopqrstuvwxyz";
-        let result = decode(&encoded, character_set).expect("Failed to decode");
-        assert_eq!(result, "aze"); // Assuming this is the expected decoded string
+    fn test_compare_with_max_words() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "This is a sentence. And another one.";
+        let max = max_representable_code(character_set);
+        assert_eq!(
+            compare_with_max_words("HELLO", cover_text, character_set, max),
+            compare("HELLO", cover_text, character_set)
+        );
+        assert_eq!(
+            compare_with_max_words("ZAZ", cover_text, character_set, 5),
+            Err(CompareError::SentenceTooLong {
+                sentence: 0,
+                words: 26,
+                max: 5
+            })
+        );
+        assert_eq!(
+            compare_with_max_words("A1", cover_text, character_set, 5),
+            Err(CompareError::CharacterNotFound('1'))
+        );
+        assert_eq!(max_representable_code(""), 0);
     }
 
     #[test]
-    fn test_decode_with_empty_input() {
-        let encoded = vec![];
-        let character_set = "abcdefghijklmnopqrstuvwxyz";
-        let result = decode(&encoded, character_set).expect("Failed to decode");
-        assert_eq!(result, ""); // Decoding an empty vector should result in an empty string
+    fn test_split_sentences_matches_encode() {
+        let input = "Hello, this is a test.\n        Does this work?\n   !   I sure hope so";
+        let sentences = split_sentences(input);
+        assert_eq!(
+            sentences,
+            vec!["Hello, this is a test", "Does this work", "I sure hope so"]
+        );
+        let counts: Vec<usize> = sentences
+            .iter()
+            .map(|sentence| sentence.split_whitespace().count())
+            .collect();
+        assert_eq!(counts, encode(input).expect("Failed to encode"));
+        let ranges: Vec<&str> = encode_with_ranges(input)
+            .expect("Failed to encode")
+            .into_iter()
+            .map(|(range, _)| &input[range])
+            .collect();
+        assert_eq!(sentences, ranges);
+        assert!(split_sentences(" ... ").is_empty());
     }
 
     #[test]
-    fn test_basic_wps_encode() {
-        let input = "Hello, this is a test.\n        Does this work?\n        I sure hope so.";
-        let expected = vec![5, 3, 4];
-        let result = encode(input).expect("Failed to encode");
-        assert_eq!(result, expected);
+    fn test_fill_template_round_trip() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let counts = message_to_counts("HELLO", character_set).expect("Failed to convert");
+        let cover_text = fill_template(&counts, "word", '.');
+        assert_eq!(encode(&cover_text).expect("Failed to encode"), counts);
+        assert_eq!(
+            decode(
+                &encode(&cover_text).expect("Failed to encode"),
+                character_set
+            ),
+            Ok("HELLO".to_string())
+        );
+        assert_eq!(fill_template(&[1, 0, 2], "a", '?'), "a? ? a a?");
+        assert_eq!(fill_template(&[], "a", '.'), "");
     }
 
     #[test]
-    fn test_incomplete_sentence_encode() {
-        let input = "Hello. This is a great tool. .. Bad sentence punctuation";
-        let expected = vec![1, 5, 3];
-        let result = encode(input).expect("Failed to encode");
-        assert_eq!(result, expected);
+    fn test_encode_cps_vs_wps() {
+        let input = "Hello, this is a test.\n        Does this work?\n ..  I sure hope so";
+        assert_eq!(encode(input), Ok(vec![5, 3, 4]));
+        assert_eq!(encode_cps(input), Ok(vec![17, 12, 11]));
+        assert_eq!(
+            encode_cps("Non-ascii ö."),
+            Err(EncodingError::NonAsciiInput)
+        );
+        assert_eq!(encode_cps(" . ! "), Err(EncodingError::NoValidSentences));
     }
 
     #[test]
-    fn test_normal_decode() {
-        let hidden_msg = "SECRET";
-        let cover_text = "\n        Hello Bob, I hope you are well and good, I would like to know if you are free tomorrow.\n        Hmm, How about a picnic?\n\n        At the park?\n\n        I would very much look forward to that, but will jane bring her dog, i was just wondering?\n\n        Anyway i would like to.\n        But how many days until Sara will be making her famous lemon drizzle cake, it was to die for, before.\n        ";
+    fn test_compare_and_extend_round_trip() {
         let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let encoded = encode(cover_text).unwrap(); // Note that the 'expect' method could be used here as well.
-        let result = decode(&encoded, character_set).expect("Failed to decode");
-        assert_eq!(result, hidden_msg);
+        let covers = [
+            "This is a sentence. And another one!",
+            "One two three four five six seven eight nine ten. Eleven twelve? Thirteen. Fourteen. Fifteen!",
+            "",
+            " ... ",
+        ];
+        for cover_text in covers {
+            let stego_text = compare_and_extend("HELLO", cover_text, character_set, "word")
+                .expect("Failed to compare");
+            let encoded = encode(&stego_text).expect("Failed to encode");
+            assert_eq!(
+                decode(&encoded, character_set).expect("Failed to decode"),
+                "HELLO"
+            );
+        }
+        assert_eq!(
+            compare_and_extend("", "Some cover.", character_set, "word"),
+            Ok(String::new())
+        );
+        assert_eq!(
+            compare_and_extend("HI", "", character_set, "two words"),
+            Err(CompareError::InvalidFillerWord("two words".to_string()))
+        );
+        assert_eq!(
+            compare_and_extend("H1", "", character_set, "word"),
+            Err(CompareError::CharacterNotFound('1'))
+        );
     }
 
     #[test]
-    fn test_compare_exact_match() {
-        let secret_message = "HELLO";
+    fn test_index_base_consistency() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
         let cover_text = "This is a sentence. And another one.";
+        assert_eq!(IndexBase::default(), IndexBase::One);
+        assert_eq!(
+            compare_with_base("HELLO", cover_text, character_set, IndexBase::One),
+            compare("HELLO", cover_text, character_set)
+        );
+        assert_eq!(
+            message_to_counts_with_base("HELLO", character_set, IndexBase::One),
+            message_to_counts("HELLO", character_set)
+        );
+
+        for base in [IndexBase::One, IndexBase::Zero] {
+            let counts = message_to_counts_with_base("ABBA", character_set, base)
+                .expect("Failed to convert");
+            assert_eq!(
+                decode_with_base(&counts, character_set, base),
+                Ok("ABBA".to_string())
+            );
+        }
+        assert_eq!(
+            message_to_counts_with_base("ABBA", character_set, IndexBase::Zero),
+            Ok(vec![0, 1, 1, 0])
+        );
+        assert_eq!(
+            compare_with_base("ABBA", "One. Two.", character_set, IndexBase::Zero),
+            Ok(vec![-1, 0, 1, 0])
+        );
+        assert_eq!(
+            decode_with_base(&[0, 27], character_set, IndexBase::Zero),
+            Ok("AB".to_string())
+        );
+    }
+
+    #[test]
+    fn test_checksum_round_trip_and_tampering() {
         let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let result = compare(secret_message, cover_text, character_set).expect("Failed to compare");
-        assert_eq!(result, vec![4, 2, 12, 12, 15]);
+        let cover_text = "Hello, this is a test. Does this work? I sure hope so.";
+        let encoded = encode_with_checksum(cover_text, character_set).expect("Failed to encode");
+        assert_eq!(encoded, vec![5, 3, 4, 13]);
+        assert_eq!(
+            decode_verify_checksum(&encoded, character_set),
+            decode(&encoded[..3], character_set)
+        );
+
+        let mut tampered = encoded;
+        tampered[1] += 1;
+        assert_eq!(
+            decode_verify_checksum(&tampered, character_set),
+            Err(DecodingError::ChecksumMismatch)
+        );
+        assert_eq!(
+            decode_verify_checksum(&[], character_set),
+            Err(DecodingError::ChecksumMismatch)
+        );
+        assert_eq!(
+            encode_with_checksum(cover_text, ""),
+            Err(CompareError::DecodingError(
+                DecodingError::EmptyCharacterSet
+            ))
+        );
     }
 
     #[test]
-    fn test_compare_length_mismatch() {
-        let secret_message = "HELLO";
-        let cover_text = "This is a sentence. This is another. And yet another.";
+    fn test_split_sentences_with_terminators_rebuilds_text() {
+        let input = "Hello, this is a test.\n  Does this work?! I sure hope so";
+        let sentences = split_sentences_with_terminators(input);
+        assert_eq!(
+            sentences,
+            vec![
+                ("Hello, this is a test", Some('.')),
+                ("\n  Does this work", Some('?')),
+                ("", Some('!')),
+                (" I sure hope so", None),
+            ]
+        );
+        let mut rebuilt = String::new();
+        for (body, terminator) in sentences {
+            rebuilt.push_str(body);
+            rebuilt.extend(terminator);
+        }
+        assert_eq!(rebuilt, input);
+        assert_eq!(
+            split_sentences_with_terminators("Done."),
+            vec![("Done", Some('.'))]
+        );
+        assert!(split_sentences_with_terminators("").is_empty());
+    }
+
+    #[test]
+    fn test_collapse_terminator_runs() {
+        let input = "One... two... three.";
+        let collapsed = EncodeOptions {
+            collapse_terminator_runs: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(encode(input), Ok(vec![1, 1, 1]));
+        assert_eq!(encode_with(input, &collapsed), Ok(vec![1, 1, 1]));
+
+        let default = EncodeOptions::default();
+        let bodies: Vec<&str> = sentence_ranges(input, &default)
+            .map(|(range, _)| &input[range])
+            .collect();
+        assert_eq!(bodies, vec!["One", "", "", " two", "", "", " three"]);
+        let bodies: Vec<&str> = sentence_ranges(input, &collapsed)
+            .map(|(range, _)| &input[range])
+            .collect();
+        assert_eq!(bodies, vec!["One", " two", " three"]);
+
+        let bodies: Vec<(&str, Option<char>)> = sentence_ranges("Wait...?! what?", &collapsed)
+            .map(|(range, terminator)| (&"Wait...?! what?"[range], terminator))
+            .collect();
+        assert_eq!(bodies, vec![("Wait", Some('.')), (" what", Some('?'))]);
+    }
+
+    #[test]
+    fn test_generate_stego_text_round_trip() {
         let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let result = compare(secret_message, cover_text, character_set).expect("Failed to compare");
-        assert_eq!(result, vec![4, 2, 9, 12, 15]);
+        let filler_words = ["indeed", "really", "truly"];
+        let covers = [
+            "This is a sentence. And another one!",
+            "One two three four five six seven eight nine ten. Eleven twelve? Thirteen. Fourteen. Fifteen! Sixteen.",
+            "A cover without a terminator",
+        ];
+        for cover_text in covers {
+            let stego_text = generate_stego_text("HELLO", cover_text, character_set, &filler_words)
+                .expect("Failed to generate");
+            let encoded = encode(&stego_text).expect("Failed to encode");
+            assert_eq!(
+                decode(&encoded, character_set).expect("Failed to decode"),
+                "HELLO"
+            );
+        }
+        assert_eq!(
+            generate_stego_text("HI", "Cover. Text!", character_set, &[]),
+            Err(CompareError::EmptyWordPool)
+        );
+        assert_eq!(
+            generate_stego_text("HI", "Cover. Text!", character_set, &["ok", "not ok"]),
+            Err(CompareError::InvalidFillerWord("not ok".to_string()))
+        );
     }
 
     #[test]
-    fn test_compare_non_ascii_input() {
+    fn test_decode_unicode_character_set() {
+        assert_eq!(decode(&[1, 2, 3], "αβγ"), Ok("αβγ".to_string()));
+        assert_eq!(decode(&[4, 6], "αβγ"), Ok("αγ".to_string()));
+        assert_eq!(decode(&[1, 2], "😀🎉"), Ok("😀🎉".to_string()));
+    }
+
+    #[test]
+    fn test_verify_exact() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let stego_text = fill_template(
+            &message_to_counts("HELLO", character_set).expect("Failed to convert"),
+            "word",
+            '.',
+        );
+        assert_eq!(verify_exact("HELLO", &stego_text, character_set), Ok(true));
+        assert_eq!(verify_exact("HELL", &stego_text, character_set), Ok(false));
+        assert_eq!(verify_exact("hello", &stego_text, character_set), Ok(false));
+        assert_eq!(verify("HELL", &stego_text, character_set), Ok(true));
+        assert_eq!(
+            verify_exact("HELLO", &stego_text, ""),
+            Err(CompareError::DecodingError(
+                DecodingError::EmptyCharacterSet
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_with_options_case() {
+        let encoded = [8, 5, 12, 12, 15];
+        let decode_as = |character_set, case| {
+            decode_with_options(&encoded, character_set, &DecodeOptions { case })
+        };
+        let upper = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let lower = "abcdefghijklmnopqrstuvwxyz";
+        assert_eq!(decode_as(upper, Case::Preserve), Ok("HELLO".to_string()));
+        assert_eq!(decode_as(upper, Case::Lower), Ok("hello".to_string()));
+        assert_eq!(decode_as(lower, Case::Upper), Ok("HELLO".to_string()));
+        assert_eq!(decode_as(lower, Case::Preserve), Ok("hello".to_string()));
+        assert_eq!(
+            decode_with_options(&encoded, upper, &DecodeOptions::default()),
+            decode(&encoded, upper)
+        );
+        assert_eq!(
+            decode_as("", Case::Lower),
+            Err(DecodingError::EmptyCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_encode_iter_checked_matches_encode() {
+        let input = "Hello, this is a test.\n        Does this work? .. I sure hope so";
+        let result: Vec<usize> = encode_iter_checked(input)
+            .expect("Failed to encode")
+            .collect();
+        assert_eq!(result, encode(input).expect("Failed to encode"));
+        assert!(matches!(
+            encode_iter_checked("Non-ascii ö."),
+            Err(EncodingError::NonAsciiInput)
+        ));
+        assert_eq!(encode_iter_checked(" ... ").map(Iterator::count), Ok(0));
+    }
+
+    #[test]
+    fn test_encode_with_spans() {
+        let input = "Hello, this is a test.\n        Does this work?\n  ..  I sure hope so";
+        let spans = encode_with_spans(input).expect("Failed to encode");
+        assert_eq!(
+            spans,
+            vec![
+                (5, "Hello, this is a test"),
+                (3, "Does this work"),
+                (4, "I sure hope so"),
+            ]
+        );
+        let counts: Vec<usize> = spans.iter().map(|&(count, _)| count).collect();
+        assert_eq!(counts, encode(input).expect("Failed to encode"));
+        assert_eq!(
+            encode_with_spans("Non-ascii ö."),
+            Err(EncodingError::NonAsciiInput)
+        );
+        assert_eq!(
+            encode_with_spans("..."),
+            Err(EncodingError::NoValidSentences)
+        );
+    }
+
+    #[test]
+    fn test_encoder_builder_combinations() {
+        let input = "Dr. Smith paid 2.50 here; well-known fact. Next!";
+        assert_eq!(Encoder::new().encode(input), encode(input));
+        assert_eq!(
+            Encoder::new()
+                .abbreviations(&["Dr"])
+                .keep_decimal_numbers(true)
+                .encode(input),
+            Ok(vec![7, 1])
+        );
+        assert_eq!(
+            Encoder::new()
+                .delimiters(&[';', '!'])
+                .split_hyphens(true)
+                .encode(input),
+            Ok(vec![5, 4])
+        );
+        let options = EncodeOptions {
+            allow_non_ascii: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(Encoder::new().allow_non_ascii(true).options(), &options);
+        assert_eq!(Encoder::from(options).encode("Ünïcode text."), Ok(vec![2]));
+    }
+
+    #[test]
+    fn test_word_policy_punctuation_tokens() {
+        let skip = Encoder::new();
+        let all = Encoder::new().word_policy(WordPolicy::All);
+        assert_eq!(encode("Hello -- world."), Ok(vec![2]));
+        assert_eq!(all.encode("Hello -- world."), Ok(vec![3]));
+        // An ASCII ellipsis ends sentences, but the Unicode one is a standalone punctuation token.
+        assert_eq!(
+            skip.clone()
+                .allow_non_ascii(true)
+                .encode("Wait \u{2026} what?"),
+            Ok(vec![2])
+        );
+        assert_eq!(
+            skip.encode("A state-of-the-art tool, don't you think? Route 66 -- yes."),
+            Ok(vec![6, 3])
+        );
+        assert_eq!(
+            Encoder::new()
+                .word_policy(WordPolicy::Alphabetic)
+                .encode("Route 66 -- yes."),
+            Ok(vec![2])
+        );
+        assert_eq!(
+            EncodeOptions::default().word_policy,
+            WordPolicy::SkipPunctuation
+        );
+    }
+
+    #[test]
+    fn test_punctuation_tokens_skipped_everywhere() {
+        let text = "Well -- here we go. Then - a pause -- and more words.";
+        let counts = encode(text).expect("Failed to encode");
+        assert_eq!(counts, vec![4, 6]);
+        assert_eq!(encode_iter(text).collect::<Vec<_>>(), counts);
+        #[cfg(feature = "std")]
+        assert_eq!(encode_reader(text.as_bytes()), Ok(counts));
+        assert_eq!(
+            encode_detailed(text).expect("Failed to encode")[0].words,
+            vec!["Well", "here", "we", "go"]
+        );
+        assert_eq!(encode_with_spans(text).expect("Failed to encode")[0].0, 4);
+
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let changes = compare("BC", text, character_set).expect("Failed to compare");
+        assert_eq!(changes, vec![-2, -3]);
+        let stego_text = apply_changes(text, &changes, "x").expect("Failed to apply");
+        assert_eq!(stego_text, "Well -- here. Then - a pause.");
+        assert_eq!(extract(&stego_text, character_set), Ok("BC".to_string()));
+        assert_eq!(
+            pad_sentence("One -- two.", 3, &["x"]),
+            Ok("One -- two x.".to_string())
+        );
+        assert_eq!(
+            apply_changes(text, &[1], "--"),
+            Err(CompareError::InvalidFillerWord("--".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_decode_until_sentinel() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ#";
+        let encoded = [8, 5, 12, 12, 15, 27, 3, 1, 2];
+        assert_eq!(
+            decode_until(&encoded, character_set, '#'),
+            Ok("HELLO".to_string())
+        );
+        assert_eq!(decode(&encoded, character_set), Ok("HELLO#CAB".to_string()));
+        // 54 wraps around onto the sentinel's position.
+        assert_eq!(
+            decode_until(&[8, 0, 9, 54, 1], character_set, '#'),
+            Ok("HI".to_string())
+        );
+        assert_eq!(
+            decode_until(&[8, 9], character_set, '@'),
+            decode(&[8, 9], character_set)
+        );
+        assert_eq!(
+            decode_until(&[1], "", '#'),
+            Err(DecodingError::EmptyCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_pad_sentence() {
+        let filler_words = ["truly", "quite"];
+        assert_eq!(
+            pad_sentence("Hello world.", 5, &filler_words),
+            Ok("Hello world truly quite truly.".to_string())
+        );
+        assert_eq!(
+            pad_sentence("One two three four five?  ", 3, &filler_words),
+            Ok("One two three?".to_string())
+        );
+        assert_eq!(
+            pad_sentence("Exactly three words!", 3, &filler_words),
+            Ok("Exactly three words!".to_string())
+        );
+        assert_eq!(
+            pad_sentence("No terminator", 3, &filler_words),
+            Ok("No terminator truly".to_string())
+        );
+        assert_eq!(
+            pad_sentence("", 2, &filler_words),
+            Ok("truly quite".to_string())
+        );
+        assert_eq!(
+            pad_sentence("Hello.", 0, &filler_words),
+            Err(CompareError::ZeroTargetWords)
+        );
+        assert_eq!(
+            pad_sentence("Hello.", 2, &[]),
+            Err(CompareError::EmptyWordPool)
+        );
+        let padded = pad_sentence("Short one.", 8, &filler_words).expect("Failed to pad");
+        assert_eq!(encode(&padded), Ok(vec![8]));
+    }
+
+    #[test]
+    fn test_synthesize_cover() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = synthesize_cover("AB", character_set, "x").expect("Failed to synthesize");
+        assert_eq!(cover_text, "x. x x.");
+        let encoded = encode(&cover_text).expect("Failed to encode");
+        assert_eq!(decode(&encoded, character_set), Ok("AB".to_string()));
+
+        assert_eq!(synthesize_cover("", character_set, "x"), Ok(String::new()));
+        assert_eq!(
+            synthesize_cover("A1", character_set, "x"),
+            Err(CompareError::CharacterNotFound('1'))
+        );
+        assert_eq!(
+            synthesize_cover("AB", character_set, "two words"),
+            Err(CompareError::InvalidFillerWord("two words".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_char_to_code_and_code_to_char_are_inverses() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        for c in character_set.chars() {
+            let code = char_to_code(c, character_set).expect("Character not found");
+            assert_eq!(code_to_char(code, character_set), Some(c));
+        }
+        for code in 1..=26 {
+            let c = code_to_char(code, character_set).expect("Code out of range");
+            assert_eq!(char_to_code(c, character_set), Some(code));
+        }
+        assert_eq!(char_to_code('a', character_set), None);
+        assert_eq!(code_to_char(0, character_set), None);
+        assert_eq!(code_to_char(27, character_set), None);
+        assert_eq!(char_to_code('γ', "αβγ"), Some(3));
+        assert_eq!(code_to_char(2, "αβγ"), Some('β'));
+    }
+
+    #[test]
+    fn test_keep_empty_sentences() {
+        let input = "Hi. . There.";
+        let keep = EncodeOptions {
+            keep_empty_sentences: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(encode(input), Ok(vec![1, 1]));
+        assert_eq!(encode_with(input, &keep), Ok(vec![1, 0, 1]));
+        assert_eq!(
+            Encoder::new()
+                .keep_empty_sentences(true)
+                .encode("Hi.. There!"),
+            Ok(vec![1, 0, 1])
+        );
+        let encoded = encode_with(input, &keep).expect("Failed to encode");
+        assert_eq!(
+            decode_with_offset(&encoded, "ABCDE", 0),
+            Ok("BAB".to_string())
+        );
+        assert_eq!(
+            encode_with(" . .", &keep),
+            Err(EncodingError::NoValidSentences)
+        );
+    }
+
+    #[test]
+    fn test_encode_and_decode_display() {
+        let cover_text = "One two three four. One two. One two three four five six seven eight nine ten eleven twelve.";
+        let decoded =
+            encode_and_decode(cover_text, "ABCDEFGHIJKLMNOPQRSTUVWXYZ").expect("Failed to decode");
+        assert_eq!(decoded.to_string(), r#"[4, 2, 12] => "DBL""#);
+        assert_eq!(
+            encode_and_decode("", "ABC"),
+            Err(CompareError::EncodingError(EncodingError::EmptyInput))
+        );
+        assert_eq!(
+            encode_and_decode("One.", ""),
+            Err(CompareError::DecodingError(
+                DecodingError::EmptyCharacterSet
+            ))
+        );
+    }
+
+    #[test]
+    fn test_allow_unicode_whitespace() {
+        let input = "Hello\u{a0}world\u{2003}again. Zero\u{200b}width\u{200b} here.";
+        assert_eq!(encode(input), Err(EncodingError::NonAsciiInput));
+        let options = EncodeOptions {
+            allow_unicode_whitespace: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(encode_with(input, &options), Ok(vec![3, 2]));
+        assert_eq!(
+            Encoder::new()
+                .allow_unicode_whitespace(true)
+                .encode("Still\u{a0}not ascii: é."),
+            Err(EncodingError::NonAsciiInput)
+        );
+    }
+
+    #[test]
+    fn test_compare_ops_matches_compare() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "This is a sentence. This is another. And yet another. One. Two words.";
+        for secret_message in ["", "HI", "CCC", "HELLO", "HELLOWORLD"] {
+            let ops =
+                compare_ops(secret_message, cover_text, character_set).expect("Failed to compare");
+            let changes: Vec<isize> = ops
+                .iter()
+                .map(|op| match *op {
+                    SentenceOp::Add(n) | SentenceOp::NewSentence(n) => isize::try_from(n).unwrap(),
+                    SentenceOp::Remove(n) => -isize::try_from(n).unwrap(),
+                    SentenceOp::Keep => 0,
+                })
+                .collect();
+            assert_eq!(
+                Ok(changes),
+                compare(secret_message, cover_text, character_set)
+            );
+        }
+        let ops = compare_ops("CCC", cover_text, character_set).expect("Failed to compare");
+        assert_eq!(
+            ops,
+            vec![
+                SentenceOp::Remove(1),
+                SentenceOp::Keep,
+                SentenceOp::Keep,
+                SentenceOp::Remove(1),
+                SentenceOp::Remove(2)
+            ]
+        );
+        let ops = compare_ops("HELLOWORLD", cover_text, character_set).expect("Failed to compare");
+        assert_eq!(ops[5], SentenceOp::NewSentence(23));
+        assert_eq!(
+            compare_ops("HI", "", character_set),
+            Err(CompareError::EncodingError(EncodingError::EmptyInput))
+        );
+    }
+
+    #[test]
+    fn test_permutation_round_trip() {
+        let secret_message = "HELLOWORLD";
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let key: Vec<usize> = (0..26).map(|i| (i * 7 + 3) % 26).collect();
+        let other_key: Vec<usize> = (0..26).map(|i| (i * 11 + 5) % 26).collect();
+        let cover_text = "One. Two. Three.";
+
+        let changes = compare_with_permutation(secret_message, cover_text, character_set, &key)
+            .expect("Failed to compare");
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        assert_eq!(
+            decode_with_permutation(&encoded, character_set, &key),
+            Ok(secret_message.to_string())
+        );
+        assert_ne!(
+            decode_with_permutation(&encoded, character_set, &other_key),
+            Ok(secret_message.to_string())
+        );
+        assert_ne!(
+            decode(&encoded, character_set),
+            Ok(secret_message.to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_permutation() {
+        for permutation in [&[0, 1][..], &[0, 1, 1], &[0, 1, 3], &[0, 1, 2, 3]] {
+            assert_eq!(
+                decode_with_permutation(&[1], "ABC", permutation),
+                Err(DecodingError::InvalidPermutation)
+            );
+            assert_eq!(
+                compare_with_permutation("A", "One.", "ABC", permutation),
+                Err(CompareError::DecodingError(
+                    DecodingError::InvalidPermutation
+                ))
+            );
+        }
+        assert_eq!(
+            decode_with_permutation(&[1], "", &[]),
+            Err(DecodingError::EmptyCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_compare_with_checksum() {
         let secret_message = "HELLO";
-        let cover_text = "This sentence contains ö.";
         let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let result = compare(secret_message, cover_text, character_set);
-        assert!(result.is_err());
+        let cover_text = "This is a sentence. Another one here.";
+        let changes = compare_with_checksum(secret_message, cover_text, character_set)
+            .expect("Failed to compare");
+        assert_eq!(changes.len(), 6);
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        // (8 + 5 + 12 + 12 + 15) % 26 + 1 = 1
+        assert_eq!(encoded.last(), Some(&1));
+        assert_eq!(
+            decode_verify_checksum(&encoded, character_set),
+            Ok(secret_message.to_string())
+        );
+
+        let mut tampered = encoded;
+        tampered[1] += 1;
+        assert_eq!(
+            decode_verify_checksum(&tampered, character_set),
+            Err(DecodingError::ChecksumMismatch)
+        );
+        assert_eq!(
+            compare_with_checksum("", "One two.", character_set),
+            Ok(vec![-1])
+        );
+        assert_eq!(
+            compare_with_checksum("A", "One.", ""),
+            Err(CompareError::CharacterNotFound('A'))
+        );
     }
 
     #[test]
-    fn test_compare_empty_input() {
-        let secret_message = "";
-        let cover_text = "";
+    fn test_treat_newlines_as_delimiters() {
+        let options = EncodeOptions {
+            treat_newlines_as_delimiters: true,
+            keep_empty_sentences: true,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(encode("a b\r\nc"), Ok(vec![3]));
+        assert_eq!(encode_with("a b\r\nc", &options), Ok(vec![2, 1]));
+        assert_eq!(encode_with("a b\nc\rd e f", &options), Ok(vec![2, 1, 3]));
+        assert_eq!(encode_with("a\r\n\r\nb", &options), Ok(vec![1, 0, 1]));
+        assert_eq!(
+            Encoder::new()
+                .treat_newlines_as_delimiters(true)
+                .encode("roses are red\r\nviolets are blue. too\r\n"),
+            Ok(vec![3, 3, 1])
+        );
+        assert_eq!(encode_line_oriented("a b\r\nc"), Ok(vec![2, 1]));
+    }
+
+    /// Round trip with `--no-default-features`, using only `alloc` types. Unit tests always link `std`, so the
+    /// `#![no_std]` build itself is checked in CI against the `thumbv7em-none-eabihf` target.
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn test_no_std_round_trip() {
+        let secret_message: String = "NOSTD".into();
         let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
-        let result = compare(secret_message, cover_text, character_set).expect("Failed to compare");
-        assert_eq!(result, Vec::<isize>::new());
+        let cover_text = "Log line one. Log line two. Log line three.";
+
+        let changes: Vec<isize> =
+            compare(&secret_message, cover_text, character_set).expect("Failed to compare");
+        let stego_text: String = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded: Vec<usize> = encode(&stego_text).expect("Failed to encode");
+        assert_eq!(decode(&encoded, character_set), Ok(secret_message));
+    }
+
+    #[test]
+    fn test_compare_with_options_case_insensitive() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ ";
+        let cover_text = "This is a sentence. Another one here.";
+        let options = CompareOptions {
+            case_insensitive: true,
+            ..CompareOptions::default()
+        };
+        assert_eq!(
+            compare_with_options(
+                "hello",
+                cover_text,
+                character_set,
+                &CompareOptions::default()
+            ),
+            Err(CompareError::CharacterNotFound('h'))
+        );
+        let lower = compare_with_options("hello world", cover_text, character_set, &options);
+        assert!(lower.is_ok());
+        assert_eq!(
+            lower,
+            compare_with_options("HELLO WORLD", cover_text, character_set, &options)
+        );
+        assert_eq!(lower, compare("HELLO WORLD", cover_text, character_set));
+    }
+
+    #[test]
+    fn test_encode_wpp() {
+        let input = "\n\nDear Bob. How are you?\nAll is well here!\n\n  \t\n\nThe weather is fine.\r\n\r\nBye.\n";
+        assert_eq!(encode_wpp(input), Ok(vec![9, 4, 1]));
+        assert_eq!(encode(input).map(|encoded| encoded.len()), Ok(5));
+        assert_eq!(encode_wpp(" \n\n "), Err(EncodingError::EmptyInput));
+        assert_eq!(encode_wpp("Caf\u{e9}."), Err(EncodingError::NonAsciiInput));
+    }
+
+    #[test]
+    fn test_suspicion_score() {
+        let natural = "The committee met on Tuesday to discuss the proposed changes to the budget. \
+            Several members raised concerns about the rising cost of maintaining the old library building downtown. \
+            After a long debate, the chair suggested postponing the final vote until next month. \
+            Nobody objected to that. \
+            The minutes of the meeting will be published on the council website by the end of the week, \
+            together with the full list of attendees and the documents that were presented during the session.";
+        let stego = synthesize_cover("ATTACKATDAWN", "ABCDEFGHIJKLMNOPQRSTUVWXYZ", "word")
+            .expect("Failed to synthesize");
+
+        let natural_score = suspicion_score(natural).expect("Failed to score");
+        let stego_score = suspicion_score(&stego).expect("Failed to score");
+        assert!((0.0..=1.0).contains(&natural_score));
+        assert!((0.0..=1.0).contains(&stego_score));
+        assert!(natural_score < 0.5, "natural score {natural_score}");
+        assert!(stego_score > 0.7, "stego score {stego_score}");
+        assert_eq!(suspicion_score(""), Err(EncodingError::EmptyInput));
+    }
+
+    #[test]
+    fn test_interleaved_round_trip() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "This is a sentence. Another one here. And a third.";
+        let changes = compare_interleaved(&["HELLO", "WORLD"], cover_text, character_set)
+            .expect("Failed to compare");
+        assert_eq!(changes.len(), 10);
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        assert_eq!(
+            decode_interleaved(&encoded, character_set, 2),
+            Ok(vec!["HELLO".to_string(), "WORLD".to_string()])
+        );
+        assert_eq!(
+            decode_interleaved(&encoded, character_set, 1),
+            Ok(vec!["HWEOLRLLOD".to_string()])
+        );
+
+        assert_eq!(
+            decode_interleaved(&encoded, character_set, 0),
+            Err(DecodingError::ZeroStreams)
+        );
+        assert_eq!(
+            compare_interleaved(&[], cover_text, character_set),
+            Err(CompareError::DecodingError(DecodingError::ZeroStreams))
+        );
+        assert_eq!(
+            compare_interleaved(&["HI", "BYE"], cover_text, character_set),
+            Err(CompareError::MismatchedSecretLengths)
+        );
+    }
+
+    #[test]
+    fn test_word_policy_alphabetic_skips_numbers() {
+        let alphabetic = Encoder::new().word_policy(WordPolicy::Alphabetic);
+        assert_eq!(encode("We saw 5 cats."), Ok(vec![4]));
+        assert_eq!(alphabetic.encode("We saw 5 cats."), Ok(vec![3]));
+        assert_eq!(
+            alphabetic.encode("We earned 3 , 000 , 000 dollars . Wow!"),
+            Ok(vec![3, 1])
+        );
+    }
+
+    #[test]
+    fn test_compare_optimal_minimizes_edits() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text =
+            "One. Two words. A much longer sentence of exactly ten words right here. Three words here. Four words are here.";
+        let secret_message = "JCAEB";
+        let total = |changes: &[isize]| changes.iter().map(|c| c.unsigned_abs()).sum::<usize>();
+
+        let positional =
+            compare(secret_message, cover_text, character_set).expect("Failed to compare");
+        let (changes, assignment) =
+            compare_optimal(secret_message, cover_text, character_set).expect("Failed to compare");
+        assert_eq!(total(&positional), 9 + 1 + 9 + 2 + 2);
+        assert_eq!(changes, vec![0, 0, 0, 0, 1]);
+        assert_eq!(assignment, vec![2, 3, 0, 4, 1]);
+
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        let codes: Vec<usize> = assignment
+            .iter()
+            .map(|&sentence| encoded[sentence])
+            .collect();
+        assert_eq!(
+            decode(&codes, character_set),
+            Ok(secret_message.to_string())
+        );
+
+        let (changes, assignment) =
+            compare_optimal("HELLOWORLD", cover_text, character_set).expect("Failed to compare");
+        assert_eq!(changes.len(), 10);
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        let codes: Vec<usize> = assignment
+            .iter()
+            .map(|&sentence| encoded[sentence])
+            .collect();
+        assert_eq!(decode(&codes, character_set), Ok("HELLOWORLD".to_string()));
+
+        let (changes, assignment) =
+            compare_optimal("B", cover_text, character_set).expect("Failed to compare");
+        assert_eq!((changes, assignment), (vec![0, 0, 0, 0, 0], vec![1]));
+        assert_eq!(
+            compare_optimal("", cover_text, character_set),
+            Ok((vec![], vec![]))
+        );
+    }
+
+    #[test]
+    fn test_decode_iter_matches_decode() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let counts = vec![19, 0, 5, 3, 18, 5, 20, 53];
+        let decoded: Result<String, _> =
+            decode_iter(counts.iter().copied(), character_set).collect();
+        assert_eq!(decoded, decode(&counts, character_set));
+        assert_eq!(decoded, Ok("SECRETA".to_string()));
+
+        let mut lazy = decode_iter((1..).map(|i| i % 3), "AB");
+        assert_eq!(lazy.next(), Some(Ok('A')));
+        assert_eq!(lazy.next(), Some(Ok('B')));
+        assert_eq!(lazy.next(), Some(Ok('A')));
+
+        let decoded: Result<String, _> = decode_iter(counts, "").collect();
+        assert_eq!(decoded, Err(DecodingError::EmptyCharacterSet));
+        assert_eq!(decode_iter(Vec::new(), "").count(), 1);
+    }
+
+    #[test]
+    fn test_encode_empty_input() {
+        assert_eq!(encode(""), Err(EncodingError::EmptyInput));
+        assert_eq!(encode("   "), Err(EncodingError::EmptyInput));
+        assert_eq!(encode(" \n\t "), Err(EncodingError::EmptyInput));
+        assert_eq!(encode("..."), Err(EncodingError::NoValidSentences));
+        assert_eq!(encode("... !!!"), Err(EncodingError::NoValidSentences));
+        assert_eq!(encode_cps("  "), Err(EncodingError::EmptyInput));
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(encode_reader(&b" \n"[..]), Err(EncodingError::EmptyInput));
+            assert_eq!(
+                encode_reader(&b".."[..]),
+                Err(EncodingError::NoValidSentences)
+            );
+        }
+        assert_eq!(
+            compare_and_extend("HI", "  ", "ABCDEFGHIJKLMNOPQRSTUVWXYZ", "x"),
+            Ok("x x x x x x x x. x x x x x x x x x.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let payload = [0u8, 1, 127, 255, 42, 0];
+        let cover_text = "A short cover. With two sentences.";
+        let changes = compare_bytes(&payload, cover_text).expect("Failed to compare");
+        assert_eq!(changes.len(), payload.len());
+        let stego_text = apply_changes(cover_text, &changes, "x").expect("Failed to apply");
+        let encoded = encode(&stego_text).expect("Failed to encode");
+        assert_eq!(encoded, encode_bytes(&payload));
+        assert_eq!(decode_bytes(&encoded), Ok(payload.to_vec()));
+
+        let cover_text = fill_template(&encode_bytes(b"hi"), "x", '.');
+        let encoded = encode(&cover_text).expect("Failed to encode");
+        assert_eq!(decode_bytes(&encoded), Ok(b"hi".to_vec()));
+
+        assert_eq!(decode_bytes(&[0, 1]), Ok(vec![0]));
+        assert_eq!(decode_bytes(&[257]), Err(DecodingError::InvalidCode(257)));
+        assert_eq!(compare_bytes(&[], cover_text.as_str()), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_include_unterminated() {
+        let input = "A b c. d e";
+        let drop = EncodeOptions {
+            include_unterminated: false,
+            ..EncodeOptions::default()
+        };
+        assert_eq!(
+            encode_with(input, &EncodeOptions::default()),
+            Ok(vec![3, 2])
+        );
+        assert_eq!(encode_with(input, &drop), Ok(vec![3]));
+        assert_eq!(
+            Encoder::new()
+                .include_unterminated(false)
+                .encode("A b c. d e! "),
+            Ok(vec![3, 2])
+        );
+        assert_eq!(
+            encode_with("no terminator at all", &drop),
+            Err(EncodingError::NoValidSentences)
+        );
+    }
+
+    #[test]
+    fn test_decode_lossy() {
+        let character_set = "ABCDE";
+        assert_eq!(decode_lossy(&[2, 0, 6, 5, 100], character_set, '?'), "B?E?");
+        assert_eq!(
+            decode_lossy(&[1, 2, 3], character_set, '?'),
+            decode_strict(&[1, 2, 3], character_set).expect("Failed to decode")
+        );
+        assert_eq!(decode_lossy(&[1, 2], "", '\u{fffd}'), "\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn test_colliding_codes() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let collisions = colliding_codes(character_set, 52);
+        assert_eq!(collisions.len(), 26);
+        assert_eq!(collisions[&'A'], vec![1, 27]);
+        assert_eq!(collisions[&'Z'], vec![26, 52]);
+        assert!(collisions.values().all(|counts| counts.len() == 2));
+        for (c, counts) in &collisions {
+            for &count in counts {
+                assert_eq!(decode(&[count], character_set), Ok(c.to_string()));
+            }
+        }
+        assert!(colliding_codes("", 52).is_empty());
+        assert!(colliding_codes(character_set, 0).is_empty());
+    }
+
+    #[test]
+    fn test_best_cover() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let covers = [
+            "One two three four five six seven eight nine ten. One two three.",
+            "One two three four five six seven eight. One two three four five.",
+            "One. Two.",
+        ];
+        assert_eq!(compare("HE", covers[1], character_set), Ok(vec![0, 0]));
+        assert_eq!(best_cover("HE", &covers, character_set), Ok((covers[1], 0)));
+        assert_eq!(
+            best_cover("HE", &[covers[0], covers[2]], character_set),
+            Ok((covers[0], 4))
+        );
+        assert_eq!(
+            best_cover("HE", &[], character_set),
+            Err(CompareError::NoCoverTexts)
+        );
+    }
+
+    #[test]
+    fn test_charset_index_conversions() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let index = CharsetIndex::try_from(character_set).expect("Invalid charset");
+        assert_eq!(
+            index,
+            CharsetIndex::new(character_set).expect("Invalid charset")
+        );
+        assert_eq!(character_set.parse::<CharsetIndex>(), Ok(index.clone()));
+        assert_eq!(decode_with_index(&[8, 9], &index), "HI");
+        assert_eq!(
+            CharsetIndex::try_from(""),
+            Err(DecodingError::EmptyCharacterSet)
+        );
+        assert_eq!(
+            "ABCB".parse::<CharsetIndex>(),
+            Err(DecodingError::DuplicateCharacter('B'))
+        );
+    }
+
+    #[test]
+    fn test_text_stats() {
+        let paragraph = "The quick brown fox jumps over the lazy dog. It barked! \
+                         Then the fox ran away into the dark forest. Why?";
+        let stats = text_stats(paragraph).expect("Failed to encode");
+        assert_eq!(stats.sentence_count, 4);
+        assert_eq!(stats.total_words, 21);
+        assert_eq!(stats.min_words, 1);
+        assert_eq!(stats.max_words, 9);
+        assert!((stats.mean_words - 5.25).abs() < f64::EPSILON);
+        assert_eq!(text_stats("   "), Err(EncodingError::EmptyInput));
+    }
+
+    #[test]
+    fn test_word_counting_unicode() {
+        let input = "我爱 Rust 编程. Don't stop-now!";
+        assert_eq!(encode(input), Err(EncodingError::NonAsciiInput));
+        let unicode = Encoder::new().word_counting(WordCounting::Unicode);
+        assert_eq!(unicode.encode(input), Ok(vec![5, 3]));
+        assert_eq!(
+            unicode
+                .delimiters(&['。', '.'])
+                .encode("東京に行きました。שלום עולם -- مرحبا."),
+            Ok(vec![8, 3])
+        );
+        assert_eq!(
+            Encoder::new()
+                .allow_non_ascii(true)
+                .encode("我爱 Rust 编程."),
+            Ok(vec![3])
+        );
+    }
+
+    #[test]
+    fn test_compare_with_options_strict_capacity() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "One two. Three four five. Six.";
+        let strict = CompareOptions {
+            strict_capacity: true,
+            ..CompareOptions::default()
+        };
+        assert_eq!(
+            compare_with_options("HELLO", cover_text, character_set, &strict),
+            Err(CompareError::InsufficientCapacity { have: 3, need: 5 })
+        );
+        assert_eq!(
+            compare_with_options(
+                "HELLO",
+                cover_text,
+                character_set,
+                &CompareOptions::default()
+            )
+            .map(|changes| changes.len()),
+            Ok(5)
+        );
+        assert_eq!(
+            compare_with_options("HEL", cover_text, character_set, &strict),
+            compare("HEL", cover_text, character_set)
+        );
+    }
+
+    #[test]
+    fn test_salted_round_trip() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "This is a sentence. Another one here. And a third. Four. Five.";
+        let stego = |salt| {
+            let changes = compare_salted("HELLO", cover_text, character_set, salt)
+                .expect("Failed to compare");
+            let stego_text = apply_changes(cover_text, &changes, "word").expect("Failed to apply");
+            encode(&stego_text).expect("Failed to encode")
+        };
+        let encoded = stego(7);
+        assert_eq!(
+            decode_salted(&encoded, character_set, 7),
+            Ok("HELLO".to_string())
+        );
+        assert_ne!(
+            decode_salted(&encoded, character_set, 8),
+            Ok("HELLO".to_string())
+        );
+        assert_ne!(encoded, stego(8));
+        assert_ne!(decode(&encoded, character_set), Ok("HELLO".to_string()));
+        assert_eq!(
+            compare_salted("HEL?", cover_text, character_set, 7),
+            Err(CompareError::CharacterNotFound('?'))
+        );
+        assert_eq!(
+            compare_salted("A", cover_text, "", 7),
+            Err(CompareError::CharacterNotFound('A'))
+        );
+        assert_eq!(
+            decode_salted(&[1], "", 7),
+            Err(DecodingError::EmptyCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_compare_padded() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "One two. Three four five. Six. Seven eight. Nine ten eleven twelve.";
+        let changes =
+            compare_padded("CAT", cover_text, character_set, 6, 'X').expect("Failed to compare");
+        assert_eq!(changes.len(), 6);
+        assert_eq!(
+            changes,
+            compare("CATXXX", cover_text, character_set).unwrap()
+        );
+        assert_eq!(
+            compare_padded("KITTEN", cover_text, character_set, 6, 'X'),
+            compare("KITTEN", cover_text, character_set)
+        );
+        assert_eq!(
+            compare_padded("KITTENS", cover_text, character_set, 6, 'X'),
+            Err(CompareError::SecretTooLong { length: 7, max: 6 })
+        );
+        assert_eq!(
+            compare_padded("CAT", cover_text, character_set, 3, '_'),
+            Err(CompareError::CharacterNotFound('_'))
+        );
+    }
+
+    #[test]
+    fn test_printable_ratio() {
+        assert!((printable_ratio("THE QUICK FOX") - 1.0).abs() < f64::EPSILON);
+        assert!((printable_ratio("Grüße") - 1.0).abs() < f64::EPSILON);
+        assert!(printable_ratio("#4%1!?").abs() < f64::EPSILON);
+        assert!((printable_ratio("A1B2") - 0.5).abs() < f64::EPSILON);
+        assert!(printable_ratio("").abs() < f64::EPSILON);
+
+        let cover_text = "Hello world. How are you? I am fine.";
+        let garbage = encode_and_decode(cover_text, "0123456789!@#$%^&*()").expect("Failed");
+        assert!(garbage.printable_ratio() < 0.5);
+        let message = encode_and_decode(cover_text, "ABCDEFGHIJKLMNOPQRSTUVWXYZ").expect("Failed");
+        assert!((message.printable_ratio() - 1.0).abs() < f64::EPSILON);
+
+        let extracted = extract_with_ratio(cover_text, "0123456789!@#$%^&*()").expect("Failed");
+        assert_eq!(extracted.message, garbage.text);
+        assert!((extracted.printable_ratio - garbage.printable_ratio()).abs() < f64::EPSILON);
+        assert!(matches!(
+            extract_with_ratio("Héllo.", "ABC"),
+            Err(ExtractError::EncodingError(EncodingError::NonAsciiInput))
+        ));
+    }
+
+    #[test]
+    fn test_decode_tokens() {
+        let tokens = ["A", "TH", "ER"];
+        assert_eq!(decode_tokens(&[2, 3], &tokens), Ok("THER".to_string()));
+        assert_eq!(decode_tokens(&[1, 0, 5], &tokens), Ok("ATH".to_string()));
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let single: Vec<String> = character_set.chars().map(String::from).collect();
+        let single: Vec<&str> = single.iter().map(String::as_str).collect();
+        assert_eq!(
+            decode_tokens(&[8, 9, 27], &single),
+            decode(&[8, 9, 27], character_set)
+        );
+        assert_eq!(
+            decode_tokens(&[1], &[]),
+            Err(DecodingError::EmptyCharacterSet)
+        );
+    }
+
+    #[test]
+    fn test_sentences_iterator() {
+        let input = "Hello, this is a test.\n        Does this work?\n   !   I sure hope so";
+        assert_eq!(
+            Sentences::new(input).count(),
+            encode(input).expect("Failed to encode").len()
+        );
+        let counts: Vec<usize> = Sentences::new(input)
+            .map(|sentence| sentence.split_whitespace().count())
+            .collect();
+        assert_eq!(counts, encode(input).expect("Failed to encode"));
+        assert_eq!(Sentences::new(input).last(), Some("I sure hope so"));
+        assert_eq!(Sentences::new(" ... ").next(), None);
+        assert_eq!(Sentences::new("").next(), None);
+    }
+
+    #[test]
+    fn test_changes_for_targets_large_counts() {
+        let half = isize::MAX / 2;
+        let count = usize::try_from(half).unwrap() * 3;
+        assert_eq!(changes_for_targets(&[half], &[count]), Ok(vec![-2 * half]));
+        let count = usize::try_from(isize::MAX).unwrap() + 1;
+        assert_eq!(changes_for_targets(&[], &[count]), Ok(vec![isize::MIN]));
+        assert_eq!(
+            changes_for_targets(&[1, half], &[2, usize::MAX]),
+            Err(CompareError::ValueOutOfRange)
+        );
+        assert_eq!(changes_for_targets(&[half, 3], &[1]), Ok(vec![half - 1, 3]));
+    }
+
+    #[test]
+    fn test_decode_matches_decode_with_offset() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let encoded = [1, 8, 26, 27, 52, 53, 100];
+        assert_eq!(
+            decode(&encoded, character_set),
+            decode_with_offset(&encoded, character_set, 1)
+        );
+        assert_eq!(
+            decode_with_zero_policy(&[0, 2], character_set, ZeroPolicy::Error),
+            decode_with_offset(&[0, 2], character_set, 1)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_charset_last_occurrence_wins() {
+        let character_set = "ABCA";
+        let cover_text = "One two three four. One.";
+        assert_eq!(char_to_code('A', character_set), Some(4));
+        assert_eq!(compare("AB", cover_text, character_set), Ok(vec![0, 1]));
+        assert_eq!(
+            compare_packed("A", cover_text, character_set, 1),
+            Ok(vec![0, -1])
+        );
+        assert_eq!(
+            generate_cover("A", character_set, &["w"]),
+            Ok("w w w w.".to_string())
+        );
+        let salted = compare_salted("AB", cover_text, character_set, 3);
+        let unique = compare_salted("DB", cover_text, "ABCD", 3);
+        assert_eq!(salted, unique);
+    }
+
+    #[test]
+    fn test_generate_stego_text_keeps_terminators() {
+        let character_set = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let cover_text = "Wait here! Is it you? Yes it is. And a fragment";
+        let terminators: Vec<Option<char>> = split_sentences_with_terminators(cover_text)
+            .into_iter()
+            .map(|(_, terminator)| terminator)
+            .collect();
+        assert_eq!(terminators, vec![Some('!'), Some('?'), Some('.'), None]);
+
+        let stego_text = generate_stego_text("CABD", cover_text, character_set, &["so"])
+            .expect("Failed to generate");
+        assert_eq!(stego_text, "Wait here so! Is? Yes it. And a fragment so");
+        assert_eq!(extract(&stego_text, character_set), Ok("CABD".to_string()));
     }
 }
@@ -0,0 +1,282 @@
+//! Command-line interface for `stego_wps`, built with the `cli` feature.
+//!
+//! ```text
+//! stego-wps encode [FILE]
+//! stego-wps decode --charset <SET> [FILE]
+//! stego-wps decode --charset <SET> --counts <COUNTS>...
+//! stego-wps compare --secret <MSG> --cover <FILE> --charset <SET>
+//! ```
+//!
+//! Files given as `-` or left out are read from stdin. Results are written to stdout, with word counts and
+//! changes separated by spaces, so the output of `encode` can be piped straight into `decode`. With `--json`,
+//! results are printed as JSON instead: an array of numbers for `encode` and `compare`, and a string for `decode`.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use stego_wps::{compare, decode, encode_reader, CompareError, DecodingError, EncodingError};
+use thiserror::Error;
+
+/// Errors reported by the command-line interface, each with its own exit code.
+#[derive(Error, Debug)]
+enum CliError {
+    #[error("Failed to read {0}: {1}")]
+    Io(String, io::Error),
+    #[error("Invalid word count: {0:?}")]
+    InvalidCount(String),
+    #[error("Encoding failed: {0}")]
+    Encoding(#[from] EncodingError),
+    #[error("Decoding failed: {0}")]
+    Decoding(#[from] DecodingError),
+    #[error("Comparison failed: {0}")]
+    Compare(#[from] CompareError),
+}
+
+/// The result of a subcommand, printed as plain text or JSON.
+#[derive(Debug, PartialEq, Eq)]
+enum Output {
+    Counts(Vec<usize>),
+    Changes(Vec<isize>),
+    Message(String),
+}
+
+impl Output {
+    fn render(&self, json: bool) -> String {
+        match (self, json) {
+            (Self::Counts(counts), false) => join(counts),
+            (Self::Changes(changes), false) => join(changes),
+            (Self::Message(message), false) => message.clone(),
+            (Self::Counts(counts), true) => serde_json::json!(counts).to_string(),
+            (Self::Changes(changes), true) => serde_json::json!(changes).to_string(),
+            (Self::Message(message), true) => serde_json::json!(message).to_string(),
+        }
+    }
+}
+
+impl CliError {
+    /// Returns the exit code of the error. Exit code 2 is left to `clap` for usage errors.
+    const fn exit_code(&self) -> u8 {
+        match self {
+            Self::Io(..) => 1,
+            Self::InvalidCount(_) => 3,
+            Self::Encoding(_) => 4,
+            Self::Decoding(_) => 5,
+            Self::Compare(_) => 6,
+        }
+    }
+}
+
+fn cli() -> Command {
+    Command::new("stego-wps")
+        .about("Text-based steganography using the Words Per Sentence (WPS) method")
+        .version(env!("CARGO_PKG_VERSION"))
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print results as JSON")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .subcommand(
+            Command::new("encode")
+                .about("Prints the word count of each sentence of a text")
+                .arg(Arg::new("file").help("The text to encode, or - for stdin")),
+        )
+        .subcommand(
+            Command::new("decode")
+                .about("Decodes word counts into a message")
+                .arg(charset_arg())
+                .arg(
+                    Arg::new("file")
+                        .help("The word counts separated by spaces or commas, or - for stdin"),
+                )
+                .arg(
+                    Arg::new("counts")
+                        .long("counts")
+                        .help("Word counts given on the command line instead of a file")
+                        .num_args(1..)
+                        .conflicts_with("file"),
+                ),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about(
+                    "Prints the word changes per sentence needed to hide a message in a cover text",
+                )
+                .arg(
+                    Arg::new("secret")
+                        .long("secret")
+                        .help("The secret message to hide")
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("cover")
+                        .long("cover")
+                        .help("The cover text, or - for stdin")
+                        .required(true),
+                )
+                .arg(charset_arg()),
+        )
+}
+
+fn charset_arg() -> Arg {
+    Arg::new("charset")
+        .long("charset")
+        .help("The character set used for encoding")
+        .required(true)
+}
+
+fn main() -> ExitCode {
+    let matches = cli().get_matches();
+    let result = match matches.subcommand() {
+        Some(("encode", args)) => run_encode(args),
+        Some(("decode", args)) => run_decode(args),
+        Some(("compare", args)) => run_compare(args),
+        _ => unreachable!("a subcommand is required"),
+    };
+
+    match result {
+        Ok(output) => {
+            println!("{}", output.render(matches.get_flag("json")));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("stego-wps: {e}");
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+fn run_encode(args: &ArgMatches) -> Result<Output, CliError> {
+    let path = args.get_one::<String>("file").map_or("-", String::as_str);
+    Ok(Output::Counts(encode_reader(open(path)?)?))
+}
+
+fn run_decode(args: &ArgMatches) -> Result<Output, CliError> {
+    let charset = required(args, "charset");
+    let counts = match args.get_many::<String>("counts") {
+        Some(values) => values.map(String::as_str).collect::<Vec<_>>().join(" "),
+        None => read_to_string(args.get_one::<String>("file").map_or("-", String::as_str))?,
+    };
+    Ok(Output::Message(decode(&parse_counts(&counts)?, charset)?))
+}
+
+fn run_compare(args: &ArgMatches) -> Result<Output, CliError> {
+    let cover = read_to_string(required(args, "cover"))?;
+    let changes = compare(required(args, "secret"), &cover, required(args, "charset"))?;
+    Ok(Output::Changes(changes))
+}
+
+fn required<'a>(args: &'a ArgMatches, id: &str) -> &'a str {
+    args.get_one::<String>(id)
+        .map_or_else(|| unreachable!("{id} is required"), String::as_str)
+}
+
+/// Opens a file for reading, or stdin if the path is `-`.
+fn open(path: &str) -> Result<Box<dyn Read>, CliError> {
+    if path == "-" {
+        return Ok(Box::new(io::stdin()));
+    }
+    File::open(path)
+        .map(|file| Box::new(file) as Box<dyn Read>)
+        .map_err(|e| CliError::Io(path.to_string(), e))
+}
+
+fn read_to_string(path: &str) -> Result<String, CliError> {
+    let mut contents = String::new();
+    open(path)?
+        .read_to_string(&mut contents)
+        .map_err(|e| CliError::Io(path.to_string(), e))?;
+    Ok(contents)
+}
+
+/// Parses word counts separated by whitespace or commas.
+fn parse_counts(input: &str) -> Result<Vec<usize>, CliError> {
+    input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().map_err(|_| CliError::InvalidCount(s.to_string())))
+        .collect()
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        cli().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_counts() {
+        assert_eq!(
+            parse_counts("8 5, 12,12\n15\n").unwrap(),
+            vec![8, 5, 12, 12, 15]
+        );
+        assert!(matches!(
+            parse_counts("8 five"),
+            Err(CliError::InvalidCount(s)) if s == "five"
+        ));
+    }
+
+    #[test]
+    fn test_render_output() {
+        assert_eq!(Output::Counts(vec![8, 5]).render(false), "8 5");
+        assert_eq!(Output::Counts(vec![8, 5]).render(true), "[8,5]");
+        assert_eq!(Output::Changes(vec![-1, 2]).render(true), "[-1,2]");
+        assert_eq!(
+            Output::Message("HI \"X\"".to_string()).render(false),
+            "HI \"X\""
+        );
+        assert_eq!(
+            Output::Message("HI \"X\"".to_string()).render(true),
+            r#""HI \"X\"""#
+        );
+    }
+
+    #[test]
+    fn test_run_decode_and_compare() {
+        let matches = cli().get_matches_from([
+            "stego-wps",
+            "decode",
+            "--charset",
+            "ABCDE",
+            "--counts",
+            "1,2",
+            "3",
+        ]);
+        let (_, args) = matches.subcommand().unwrap();
+        assert_eq!(
+            run_decode(args).unwrap(),
+            Output::Message("ABC".to_string())
+        );
+
+        let matches = cli().get_matches_from([
+            "stego-wps",
+            "compare",
+            "--secret",
+            "AB",
+            "--cover",
+            "/nonexistent",
+            "--charset",
+            "AB",
+        ]);
+        let (_, args) = matches.subcommand().unwrap();
+        let error = run_compare(args).unwrap_err();
+        assert!(matches!(error, CliError::Io(..)));
+        assert_eq!(error.exit_code(), 1);
+    }
+}
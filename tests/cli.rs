@@ -0,0 +1,122 @@
+//! Integration tests running the `stego-wps` binary, built with the `cli` feature.
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use assert_cmd::assert::Assert;
+use assert_cmd::Command;
+
+fn run(args: &[&str], stdin: &str) -> Assert {
+    Command::new(env!("CARGO_BIN_EXE_stego-wps"))
+        .args(args)
+        .write_stdin(stdin)
+        .assert()
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, contents).expect("Failed to write temporary file");
+    path
+}
+
+#[test]
+fn test_encode_decode_pipeline() {
+    let cover_text = "Hello world. How are you today?";
+    let output = run(&["encode"], cover_text).success().stdout("2 4\n");
+
+    let counts =
+        String::from_utf8(output.get_output().stdout.clone()).expect("Output is not UTF-8");
+    run(&["decode", "--charset", "ABCD"], &counts)
+        .success()
+        .stdout("BD\n");
+}
+
+#[test]
+fn test_read_files() {
+    let cover = temp_file("cover.txt", "Hello world. How are you today?");
+    let cover = cover.to_str().expect("Path is not UTF-8");
+    run(&["encode", cover], "").success().stdout("2 4\n");
+
+    let counts = temp_file("counts.txt", "2, 4\n");
+    let counts = counts.to_str().expect("Path is not UTF-8");
+    run(&["decode", counts, "--charset", "ABCD"], "")
+        .success()
+        .stdout("BD\n");
+    run(&["decode", "-", "--charset", "ABCD"], "2 4")
+        .success()
+        .stdout("BD\n");
+
+    run(
+        &[
+            "compare",
+            "--secret",
+            "AD",
+            "--cover",
+            cover,
+            "--charset",
+            "ABCD",
+        ],
+        "",
+    )
+    .success()
+    .stdout("-1 0\n");
+}
+
+#[test]
+fn test_json_output() {
+    run(&["encode", "--json"], "Hello world. How are you today?").stdout("[2,4]\n");
+
+    run(
+        &[
+            "--json",
+            "decode",
+            "--charset",
+            "ABCD",
+            "--counts",
+            "2",
+            "4",
+        ],
+        "",
+    )
+    .stdout("\"BD\"\n");
+
+    run(
+        &[
+            "compare",
+            "--secret",
+            "AD",
+            "--cover",
+            "-",
+            "--charset",
+            "ABCD",
+            "--json",
+        ],
+        "Hello world. How are you today?",
+    )
+    .stdout("[-1,0]\n");
+}
+
+#[test]
+fn test_exit_codes() {
+    run(&[], "").code(2);
+    run(&["decode", "--charset", "AB", "--counts", "x"], "").code(3);
+    run(&["decode", "-", "--charset", "AB", "--counts", "1"], "").code(2);
+    run(&["encode"], "").code(4);
+    run(&["decode", "--charset", "", "--counts", "1"], "").code(5);
+    run(
+        &[
+            "compare",
+            "--secret",
+            "Z",
+            "--cover",
+            "-",
+            "--charset",
+            "AB",
+        ],
+        "Hi.",
+    )
+    .code(6);
+    run(&["encode", "/nonexistent"], "").code(1);
+    run(&["decode", "/nonexistent", "--charset", "AB"], "").code(1);
+}